@@ -0,0 +1,130 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use faiquery::{FastaIndex, IndexedFasta};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs,
+    io::Write,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+const CONTIG_LENGTH: usize = 1_000_000;
+const LINE_BASES: usize = 60;
+const WINDOW_SIZE: usize = 10_000;
+
+/// Counts every allocation and reallocation made by the process.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Writes a single 1Mb contig FASTA and its index to the temporary directory.
+fn write_fasta() -> (PathBuf, PathBuf) {
+    let dir = std::env::temp_dir();
+    let fasta_path = dir.join("faiquery_bench_buffer.fa");
+    let index_path = dir.join("faiquery_bench_buffer.fa.fai");
+
+    let mut fasta = fs::File::create(&fasta_path).expect("Could not create FASTA file");
+    writeln!(fasta, ">chr1").unwrap();
+    let line = b"ACGT".repeat(LINE_BASES / 4);
+    for _ in 0..CONTIG_LENGTH / LINE_BASES {
+        fasta.write_all(&line).unwrap();
+        fasta.write_all(b"\n").unwrap();
+    }
+    let length = (CONTIG_LENGTH / LINE_BASES) * LINE_BASES;
+    fs::write(
+        &index_path,
+        format!("chr1\t{}\t6\t{}\t{}\n", length, LINE_BASES, LINE_BASES + 1),
+    )
+    .expect("Could not create index file");
+    (fasta_path, index_path)
+}
+
+fn load(fasta_path: &str, index_path: &str, capacity: usize) -> IndexedFasta {
+    let index = FastaIndex::from_filepath(index_path).expect("Could not read index file");
+    IndexedFasta::with_buffer_capacity(index, fasta_path, capacity)
+        .expect("Could not read FASTA file")
+}
+
+/// Queries consecutive 10kb windows over the contig.
+fn query_windows(faidx: &mut IndexedFasta) {
+    let length = faidx.seq_len("chr1").unwrap();
+    for start in (0..length - WINDOW_SIZE).step_by(WINDOW_SIZE) {
+        black_box(
+            faidx
+                .query("chr1", start, start + WINDOW_SIZE)
+                .unwrap()
+                .as_bytes(),
+        );
+    }
+}
+
+/// Compares a growing buffer to a preallocated one over many 10kb windows.
+///
+/// The number of allocations made by the queries of a fresh `IndexedFasta` is
+/// printed for each setting before timing.
+fn buffer_capacity(c: &mut Criterion) {
+    let (fasta_path, index_path) = write_fasta();
+    let fasta_path = fasta_path.to_str().unwrap();
+    let index_path = index_path.to_str().unwrap();
+
+    for (label, capacity) in [("growing", 0), ("preallocated", WINDOW_SIZE)] {
+        let mut faidx = load(fasta_path, index_path, capacity);
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        query_windows(&mut faidx);
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        println!("query_10kb_windows_{}: {} allocations", label, allocations);
+
+        c.bench_function(&format!("query_10kb_windows_{}", label), |b| {
+            b.iter_batched(
+                || load(fasta_path, index_path, capacity),
+                |mut faidx| query_windows(&mut faidx),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+}
+
+/// Compares the buffered `query` of a 1Mb region to stripping newlines byte by byte.
+fn strip_newlines(c: &mut Criterion) {
+    let (fasta_path, index_path) = write_fasta();
+    let mut faidx = load(
+        fasta_path.to_str().unwrap(),
+        index_path.to_str().unwrap(),
+        CONTIG_LENGTH,
+    );
+    let length = faidx.seq_len("chr1").unwrap();
+
+    c.bench_function("query_1mb_region", |b| {
+        b.iter(|| black_box(faidx.query("chr1", 0, length).unwrap().len()))
+    });
+
+    let mut buffer: Vec<u8> = Vec::with_capacity(CONTIG_LENGTH);
+    c.bench_function("query_1mb_region_bytewise_filter", |b| {
+        b.iter(|| {
+            let raw = faidx.query_buffer("chr1", 0, length).unwrap();
+            buffer.clear();
+            buffer.extend(raw.iter().filter(|&&c| c != b'\n' && c != b'\r'));
+            black_box(buffer.len())
+        })
+    });
+}
+
+criterion_group!(benches, buffer_capacity, strip_newlines);
+criterion_main!(benches);
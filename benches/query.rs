@@ -0,0 +1,64 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use faiquery::{FastaIndex, IndexedFasta};
+
+const TEST_FASTA: &str = "example_data/example.fa";
+const TEST_FASTA_INDEX: &str = "example_data/example.fa.fai";
+
+fn load() -> IndexedFasta {
+    let index = FastaIndex::from_filepath(TEST_FASTA_INDEX).expect("Could not read index file");
+    IndexedFasta::new(index, TEST_FASTA).expect("Could not read FASTA file")
+}
+
+/// Tiles 10bp windows over a single contig so every query hits the same entry.
+fn same_contig(c: &mut Criterion) {
+    let mut faidx = load();
+    c.bench_function("query_same_contig", |b| {
+        b.iter(|| {
+            for start in (0..170).step_by(10) {
+                black_box(faidx.query("chr2", start, start + 6).unwrap().as_bytes());
+            }
+        })
+    });
+}
+
+/// Alternates contigs on every query so the entry must be looked up each time.
+fn alternating_contigs(c: &mut Criterion) {
+    let mut faidx = load();
+    c.bench_function("query_alternating_contigs", |b| {
+        b.iter(|| {
+            for start in (0..100).step_by(10) {
+                black_box(faidx.query("chr1", start, start + 6).unwrap().as_bytes());
+                black_box(faidx.query("chr2", start, start + 6).unwrap().as_bytes());
+            }
+        })
+    });
+}
+
+/// Tiles the same windows over one contig with and without the cached entry.
+///
+/// The uncached stream alternates between the contig and an alias of it, so every query
+/// resolves its name through the aliases and the index while reading the same bases.
+fn cached_lookup(c: &mut Criterion) {
+    let mut faidx = load();
+    faidx.set_aliases([("2".to_string(), "chr2".to_string())]);
+    let mut group = c.benchmark_group("same_contig_lookup");
+    group.bench_function("cached", |b| {
+        b.iter(|| {
+            for start in (0..170).step_by(10) {
+                black_box(faidx.query("chr2", start, start + 6).unwrap().as_bytes());
+            }
+        })
+    });
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            for (i, start) in (0..170).step_by(10).enumerate() {
+                let name = if i % 2 == 0 { "chr2" } else { "2" };
+                black_box(faidx.query(name, start, start + 6).unwrap().as_bytes());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, same_contig, alternating_contigs, cached_lookup);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use faiquery::{write_synthetic_fasta, FastaIndex, IndexedFasta};
+
+const NUM_CONTIGS: usize = 8;
+const CONTIG_LENGTH: usize = 2_000_000;
+const NUM_SCAFFOLDS: usize = 50_000;
+const LINE_BASES: usize = 60;
+
+/// Writes a synthetic genome of 8 contigs of 2Mb each to the temporary directory.
+fn genome() -> (IndexedFasta, Vec<String>) {
+    let path = std::env::temp_dir().join("faiquery_bench_genome.fa");
+    let names = (1..=NUM_CONTIGS)
+        .map(|i| format!("chr{}", i))
+        .collect::<Vec<_>>();
+    let contigs = names
+        .iter()
+        .map(|name| (name.as_str(), CONTIG_LENGTH))
+        .collect::<Vec<_>>();
+    let faidx = write_synthetic_fasta(path.to_str().unwrap(), &contigs, LINE_BASES)
+        .expect("Could not write FASTA file");
+    (faidx, names)
+}
+
+/// Returns reproducible 1kb regions spread over every contig.
+fn regions(names: &[String]) -> Vec<(&str, usize)> {
+    (0..1000)
+        .map(|i: usize| {
+            let start = i.wrapping_mul(2_654_435_761) % (CONTIG_LENGTH - 1000);
+            (names[i % names.len()].as_str(), start)
+        })
+        .collect()
+}
+
+/// Measures single 1kb region queries, with and without stripping newlines.
+fn single_region(c: &mut Criterion) {
+    let (mut faidx, names) = genome();
+    let regions = regions(&names);
+
+    let mut group = c.benchmark_group("single_region_1kb");
+    group.throughput(Throughput::Elements(regions.len() as u64));
+    group.bench_function("query", |b| {
+        b.iter(|| {
+            for &(name, start) in &regions {
+                black_box(faidx.query(name, start, start + 1000).unwrap().as_bytes());
+            }
+        })
+    });
+    group.bench_function("query_buffer", |b| {
+        b.iter(|| {
+            for &(name, start) in &regions {
+                black_box(faidx.query_buffer(name, start, start + 1000).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+/// Measures the newline-stripping throughput of querying whole contigs.
+fn strip_throughput(c: &mut Criterion) {
+    let (mut faidx, _) = genome();
+
+    let mut group = c.benchmark_group("strip_newlines");
+    group.throughput(Throughput::Bytes(CONTIG_LENGTH as u64));
+    group.bench_function("query_all_2mb", |b| {
+        b.iter(|| black_box(faidx.query_all("chr1").unwrap().len()))
+    });
+    group.finish();
+}
+
+/// Measures parsing the index of an assembly with many small scaffolds.
+fn index_parse(c: &mut Criterion) {
+    let path = std::env::temp_dir().join("faiquery_bench_scaffolds.fa");
+    let path = path.to_str().unwrap();
+    let names = (0..NUM_SCAFFOLDS)
+        .map(|i| format!("scaffold_{}", i))
+        .collect::<Vec<_>>();
+    let contigs = names
+        .iter()
+        .map(|name| (name.as_str(), 100))
+        .collect::<Vec<_>>();
+    write_synthetic_fasta(path, &contigs, LINE_BASES).expect("Could not write FASTA file");
+    let index_path = format!("{}.fai", path);
+
+    let mut group = c.benchmark_group("index_parse");
+    group.throughput(Throughput::Elements(NUM_SCAFFOLDS as u64));
+    group.bench_function("from_filepath_50k_entries", |b| {
+        b.iter(|| black_box(FastaIndex::from_filepath(&index_path).unwrap().len()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, single_region, strip_throughput, index_parse);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+/// An access pattern hint for the memory map of an `IndexedFasta`.
+///
+/// See `IndexedFasta::advise`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advice {
+    /// No special treatment (the default behavior of the memory map).
+    Normal,
+    /// Pages will be accessed in order, e.g. a genome-wide linear pass.
+    ///
+    /// The kernel reads ahead aggressively and may free pages soon after they are read.
+    Sequential,
+    /// Pages will be accessed in random order, e.g. extracting regions from a BED file.
+    ///
+    /// The kernel disables read-ahead so that each lookup only faults in what it needs.
+    Random,
+    /// Pages will be needed soon, so the kernel may start reading them in ahead of time.
+    WillNeed,
+}
+#[cfg(unix)]
+impl From<Advice> for memmap2::Advice {
+    fn from(advice: Advice) -> Self {
+        match advice {
+            Advice::Normal => memmap2::Advice::Normal,
+            Advice::Sequential => memmap2::Advice::Sequential,
+            Advice::Random => memmap2::Advice::Random,
+            Advice::WillNeed => memmap2::Advice::WillNeed,
+        }
+    }
+}
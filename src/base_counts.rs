@@ -0,0 +1,31 @@
+/// Per-base composition counts of a region.
+///
+/// Counts are case-folded, so `a` and `A` are both tallied under `a`.
+/// Any byte that is not one of `ACGTN` (e.g. IUPAC ambiguity codes) is tallied under `other`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BaseCounts {
+    pub a: usize,
+    pub c: usize,
+    pub g: usize,
+    pub t: usize,
+    pub n: usize,
+    pub other: usize,
+}
+impl BaseCounts {
+    /// Tallies a single base.
+    pub(crate) fn add(&mut self, base: u8) {
+        match base.to_ascii_uppercase() {
+            b'A' => self.a += 1,
+            b'C' => self.c += 1,
+            b'G' => self.g += 1,
+            b'T' => self.t += 1,
+            b'N' => self.n += 1,
+            _ => self.other += 1,
+        }
+    }
+
+    /// Returns the total number of bases counted.
+    pub fn total(&self) -> usize {
+        self.a + self.c + self.g + self.t + self.n + self.other
+    }
+}
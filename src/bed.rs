@@ -0,0 +1,69 @@
+use anyhow::{bail, Result};
+
+/// Parses the `chrom`, `start`, and `end` columns of a BED line.
+///
+/// Returns `None` for lines that hold no interval: blank lines, comments (`#`),
+/// and `track` or `browser` lines. Columns after the third are ignored.
+pub(crate) fn parse_bed_line(line: &str) -> Result<Option<(String, usize, usize)>> {
+    let line = line.trim_end_matches(['\n', '\r']);
+    if line.trim().is_empty()
+        || line.starts_with('#')
+        || line.starts_with("track")
+        || line.starts_with("browser")
+    {
+        return Ok(None);
+    }
+    let mut fields = line.split('\t');
+    let (name, start, end) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(name), Some(start), Some(end)) if !name.is_empty() => (name, start, end),
+        _ => bail!("Expected at least 3 tab-separated columns"),
+    };
+    let parse = |position: &str| match position.trim().parse::<usize>() {
+        Ok(position) => Ok(position),
+        Err(_) => bail!("Invalid position {}", position),
+    };
+    Ok(Some((name.to_string(), parse(start)?, parse(end)?)))
+}
+
+#[cfg(test)]
+mod testing {
+    use super::parse_bed_line;
+
+    #[test]
+    fn parse_bed3_and_beyond() {
+        assert_eq!(
+            parse_bed_line("chr1\t10\t20").unwrap(),
+            Some(("chr1".to_string(), 10, 20))
+        );
+        assert_eq!(
+            parse_bed_line("chr1\t10\t20\tgene\t0\t+\r\n").unwrap(),
+            Some(("chr1".to_string(), 10, 20))
+        );
+    }
+
+    #[test]
+    fn skip_non_interval_lines() {
+        for line in [
+            "",
+            "  ",
+            "# comment",
+            "track name=genes",
+            "browser position chr1",
+        ] {
+            assert_eq!(parse_bed_line(line).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn malformed_lines() {
+        for line in [
+            "chr1\t10",
+            "chr1 10 20",
+            "chr1\tten\t20",
+            "chr1\t10\t-5",
+            "\t1\t2",
+        ] {
+            assert!(parse_bed_line(line).is_err(), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::{bail, Result};
+use flate2::read::MultiGzDecoder;
+use std::{
+    fs::File,
+    io::{self, Read},
+};
+
+/// A BGZF `.gzi` index.
+///
+/// This maps the start of each BGZF block in the uncompressed stream to its
+/// offset in the compressed file, as written by `bgzip -i` or `samtools faidx`.
+#[derive(Debug)]
+pub(crate) struct GziIndex {
+    /// `(compressed, uncompressed)` offsets of every block, including the
+    /// implicit first block at `(0, 0)` which is not stored in the file.
+    blocks: Vec<(u64, u64)>,
+}
+impl GziIndex {
+    /// Creates a new `GziIndex` from a `Read` object.
+    ///
+    /// The entry count in the header is not trusted for preallocation, so a file that
+    /// is not a `.gzi` index fails with an error instead of a huge allocation.
+    ///
+    /// # Errors
+    ///
+    /// Error if the input ends before the header or the number of entries it declares.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let num_entries = match read_u64(&mut reader) {
+            Ok(num_entries) => num_entries,
+            Err(e) => bail!("Invalid .gzi index: missing entry count: {}", e),
+        };
+        let mut blocks = vec![(0, 0)];
+        for i in 0..num_entries {
+            match (read_u64(&mut reader), read_u64(&mut reader)) {
+                (Ok(compressed), Ok(uncompressed)) => blocks.push((compressed, uncompressed)),
+                _ => bail!(
+                    "Invalid .gzi index: expected {} entries but found {}",
+                    num_entries,
+                    i
+                ),
+            }
+        }
+        Ok(Self { blocks })
+    }
+
+    /// Creates a new `GziIndex` from a file path.
+    pub fn from_filepath(path: &str) -> Result<Self> {
+        let file = File::open(path)?;
+        Self::from_reader(file)
+    }
+
+    /// Returns the `(compressed, uncompressed)` offsets of the block containing
+    /// the uncompressed `offset`.
+    fn locate(&self, offset: u64) -> (u64, u64) {
+        // The first block always starts at uncompressed offset 0 so the
+        // partition point is at least 1.
+        let idx = self
+            .blocks
+            .partition_point(|&(_, uncompressed)| uncompressed <= offset);
+        self.blocks[idx - 1]
+    }
+
+    /// Returns the total uncompressed length of the BGZF `data`.
+    ///
+    /// Only the blocks from the last indexed block onwards are decompressed.
+    pub fn uncompressed_len(&self, data: &[u8]) -> Result<usize> {
+        let (compressed, uncompressed) = self.blocks[self.blocks.len() - 1];
+        if compressed as usize > data.len() {
+            bail!(
+                "Compressed block offset {} is beyond the end of the file ({} bytes)",
+                compressed,
+                data.len()
+            );
+        }
+        let mut decoder = MultiGzDecoder::new(&data[compressed as usize..]);
+        let remaining = io::copy(&mut decoder, &mut io::sink())?;
+        Ok((uncompressed + remaining) as usize)
+    }
+
+    /// Inflates `len` bytes starting at the uncompressed `offset` of the BGZF `data`.
+    ///
+    /// Only the blocks spanning the requested range are decompressed.
+    pub fn read(&self, data: &[u8], offset: usize, len: usize) -> Result<Vec<u8>> {
+        let (compressed, uncompressed) = self.locate(offset as u64);
+        if compressed as usize > data.len() {
+            bail!(
+                "Compressed block offset {} is beyond the end of the file ({} bytes)",
+                compressed,
+                data.len()
+            );
+        }
+        let mut decoder = MultiGzDecoder::new(&data[compressed as usize..]);
+        let skip = offset as u64 - uncompressed;
+        io::copy(&mut (&mut decoder).take(skip), &mut io::sink())?;
+        let mut buffer = vec![0; len];
+        decoder.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+/// Scans the blocks of a BGZF file and returns its `.gzi` entries.
+///
+/// Each entry is the `(compressed, uncompressed)` offset of a block start.
+/// As in a `.gzi` file, the first block and empty blocks (e.g. the EOF marker) are omitted.
+pub(crate) fn scan_blocks(data: &[u8]) -> Result<Vec<(u64, u64)>> {
+    let mut entries = Vec::new();
+    let mut compressed = 0;
+    let mut uncompressed = 0;
+    while compressed < data.len() {
+        let block_size = block_size(&data[compressed..])?;
+        if compressed + block_size > data.len() {
+            bail!("Truncated BGZF block at byte {}", compressed);
+        }
+        let isize_bytes = &data[compressed + block_size - 4..compressed + block_size];
+        let block_len = u32::from_le_bytes(isize_bytes.try_into()?) as u64;
+        if compressed > 0 && block_len > 0 {
+            entries.push((compressed as u64, uncompressed));
+        }
+        compressed += block_size;
+        uncompressed += block_len;
+    }
+    Ok(entries)
+}
+
+/// Returns the total size of the BGZF block at the start of `data` from its `BC` subfield.
+fn block_size(data: &[u8]) -> Result<usize> {
+    if data.len() < 18 || data[0] != 31 || data[1] != 139 || data[3] & 4 == 0 {
+        bail!("Invalid BGZF block header");
+    }
+    let xlen = u16::from_le_bytes([data[10], data[11]]) as usize;
+    let extra = match data.get(12..12 + xlen) {
+        Some(extra) => extra,
+        None => bail!("Truncated BGZF block header"),
+    };
+    let mut pos = 0;
+    while pos + 4 <= extra.len() {
+        let slen = u16::from_le_bytes([extra[pos + 2], extra[pos + 3]]) as usize;
+        if extra[pos] == b'B' && extra[pos + 1] == b'C' && slen == 2 && pos + 6 <= extra.len() {
+            let bsize = u16::from_le_bytes([extra[pos + 4], extra[pos + 5]]) as usize;
+            return Ok(bsize + 1);
+        }
+        pos += 4 + slen;
+    }
+    bail!("BGZF block header is missing its BC subfield")
+}
+
+/// Reads a little-endian `u64` from a `Read` object.
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod testing {
+    use super::GziIndex;
+
+    #[test]
+    fn malformed_gzi() {
+        // An empty file has no entry count
+        assert!(GziIndex::from_reader(&b""[..]).is_err());
+        // A huge entry count with no entries
+        let bogus = u64::MAX.to_le_bytes();
+        let err = GziIndex::from_reader(&bogus[..]).unwrap_err();
+        assert!(err.to_string().contains("expected"));
+        // A truncated entry
+        let mut truncated = 2u64.to_le_bytes().to_vec();
+        truncated.extend_from_slice(&[0; 20]);
+        assert!(GziIndex::from_reader(&truncated[..]).is_err());
+
+        let mut valid = 1u64.to_le_bytes().to_vec();
+        valid.extend_from_slice(&10u64.to_le_bytes());
+        valid.extend_from_slice(&100u64.to_le_bytes());
+        assert!(GziIndex::from_reader(&valid[..]).is_ok());
+    }
+}
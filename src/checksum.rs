@@ -0,0 +1,92 @@
+use crate::line_ending::is_line_break;
+use md5::Md5;
+use sha2::{Digest, Sha512};
+
+/// The number of bases hashed at a time.
+const CHUNK_SIZE: usize = 8192;
+
+/// The alphabet of unpadded URL-safe base64 used by refget identifiers.
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// A sequence checksum algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgo {
+    /// The MD5 digest as a lowercase hex string (as used in SAM `M5` tags).
+    Md5,
+    /// The GA4GH refget `sha512t24u` digest: the first 24 bytes of the SHA-512 digest
+    /// as an unpadded URL-safe base64 string.
+    Sha512t24,
+}
+
+/// Computes the checksum of a raw sequence, skipping newlines and uppercasing each base.
+///
+/// The sequence is streamed into the hasher in fixed-size chunks.
+pub(crate) fn checksum(seq: &[u8], algo: ChecksumAlgo) -> String {
+    match algo {
+        ChecksumAlgo::Md5 => {
+            let mut hasher = Md5::new();
+            for_each_chunk(seq, |chunk| hasher.update(chunk));
+            to_hex(&hasher.finalize())
+        }
+        ChecksumAlgo::Sha512t24 => {
+            let mut hasher = Sha512::new();
+            for_each_chunk(seq, |chunk| hasher.update(chunk));
+            to_base64_url(&hasher.finalize()[..24])
+        }
+    }
+}
+
+/// Calls `f` on consecutive uppercased chunks of `seq` without newline characters.
+fn for_each_chunk<F: FnMut(&[u8])>(seq: &[u8], mut f: F) {
+    let mut chunk = [0; CHUNK_SIZE];
+    let mut filled = 0;
+    for &c in seq.iter().filter(|&&c| !is_line_break(c)) {
+        chunk[filled] = c.to_ascii_uppercase();
+        filled += 1;
+        if filled == CHUNK_SIZE {
+            f(&chunk);
+            filled = 0;
+        }
+    }
+    if filled > 0 {
+        f(&chunk[..filled]);
+    }
+}
+
+/// Encodes bytes as a lowercase hex string.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Encodes bytes as an unpadded URL-safe base64 string.
+fn to_base64_url(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let mut buf = [0; 3];
+        buf[..group.len()].copy_from_slice(group);
+        let n = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+        for i in 0..=group.len() {
+            let idx = (n >> (18 - 6 * i)) & 0x3f;
+            encoded.push(BASE64_URL[idx as usize] as char);
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod testing {
+    use super::{checksum, ChecksumAlgo};
+
+    #[test]
+    fn refget_spec_example() {
+        // From the GA4GH refget specification
+        assert_eq!(
+            checksum(b"AC\ngt", ChecksumAlgo::Sha512t24),
+            "aKF498dAxcJAqme6QYQ7EZ07-fiw8Kw2"
+        );
+        assert_eq!(
+            checksum(b"ACGT", ChecksumAlgo::Md5),
+            "f1f8f4bf413b16ad135722aa4591043e"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+/// Lookup table mapping each nucleotide byte to its DNA complement.
+///
+/// `A` and `T` and `C` and `G` are swapped and `N` is mapped to itself.
+/// Case is preserved and any other byte is mapped to itself.
+pub const DNA_COMPLEMENT: [u8; 256] = complement_table(&[(b'A', b'T'), (b'C', b'G'), (b'N', b'N')]);
+
+/// Lookup table mapping each nucleotide byte to its RNA complement.
+///
+/// `A` and `U` and `C` and `G` are swapped and `N` is mapped to itself.
+/// Case is preserved and any other byte is mapped to itself.
+pub const RNA_COMPLEMENT: [u8; 256] = complement_table(&[(b'A', b'U'), (b'C', b'G'), (b'N', b'N')]);
+
+/// Lookup table mapping each nucleotide byte or IUPAC ambiguity code to its complement.
+///
+/// In addition to the DNA bases, `R` and `Y`, `K` and `M`, and `B` and `V`, and `D` and
+/// `H` are swapped, while the self-complementary `S`, `W`, and `N` are mapped to
+/// themselves. Case is preserved and any other byte is mapped to itself.
+pub const IUPAC_COMPLEMENT: [u8; 256] = complement_table(&[
+    (b'A', b'T'),
+    (b'C', b'G'),
+    (b'R', b'Y'),
+    (b'K', b'M'),
+    (b'B', b'V'),
+    (b'D', b'H'),
+    (b'S', b'S'),
+    (b'W', b'W'),
+    (b'N', b'N'),
+]);
+
+/// Builds a table mapping every byte to itself except the given complementary pairs,
+/// which are swapped in both upper and lower case.
+const fn complement_table(pairs: &[(u8, u8)]) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = i as u8;
+        i += 1;
+    }
+    let mut i = 0;
+    while i < pairs.len() {
+        let (a, b) = pairs[i];
+        table[a as usize] = b;
+        table[b as usize] = a;
+        table[a.to_ascii_lowercase() as usize] = b.to_ascii_lowercase();
+        table[b.to_ascii_lowercase() as usize] = a.to_ascii_lowercase();
+        i += 1;
+    }
+    table
+}
+
+/// Returns `true` if the byte is a nucleotide or IUPAC ambiguity code (in either case).
+///
+/// This is used to guard the nucleotide-only methods against protein sequence.
+pub(crate) fn is_nucleotide(c: u8) -> bool {
+    matches!(
+        c.to_ascii_uppercase(),
+        b'A' | b'C'
+            | b'G'
+            | b'T'
+            | b'U'
+            | b'N'
+            | b'R'
+            | b'Y'
+            | b'S'
+            | b'W'
+            | b'K'
+            | b'M'
+            | b'B'
+            | b'D'
+            | b'H'
+            | b'V'
+            | b'-'
+    )
+}
+
+/// Errors if a raw sequence contains a byte that is not a nucleotide (see `is_nucleotide`).
+pub(crate) fn ensure_nucleotide(seq: &[u8], name: &str) -> anyhow::Result<()> {
+    match seq
+        .iter()
+        .find(|&&c| !is_nucleotide(c) && c != b'\n' && c != b'\r')
+    {
+        Some(&c) => anyhow::bail!(
+            "Reverse complement requires a nucleotide sequence but {} contains '{}'",
+            name,
+            c as char
+        ),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::{DNA_COMPLEMENT, IUPAC_COMPLEMENT, RNA_COMPLEMENT};
+
+    fn complement(seq: &[u8], table: &[u8; 256]) -> Vec<u8> {
+        seq.iter().map(|&c| table[c as usize]).collect()
+    }
+
+    #[test]
+    fn complement_tables() {
+        assert_eq!(
+            complement(b"ACGTNacgtnU>", &DNA_COMPLEMENT),
+            b"TGCANtgcanU>"
+        );
+        assert_eq!(complement(b"ACGUNacgun", &RNA_COMPLEMENT), b"UGCANugcan");
+        assert_eq!(
+            complement(b"ACGTRYKMBVDHSWNrykm", &IUPAC_COMPLEMENT),
+            b"TGCAYRMKVBHDSWNyrmk"
+        );
+        // Every table is an involution
+        for table in [&DNA_COMPLEMENT, &RNA_COMPLEMENT, &IUPAC_COMPLEMENT] {
+            for c in 0..=255u8 {
+                assert_eq!(table[table[c as usize] as usize], c);
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+/// Options for the composition methods `gc_content_with` and `base_counts_with`.
+///
+/// By default gap bytes are counted like any other byte: they are part of the
+/// denominator of the GC content and are tallied as `other` in `BaseCounts`, which is
+/// the behavior of `gc_content` and `base_counts`. With `skip_gaps` the bytes in
+/// `gap_bytes` are excluded from both the counts and the total, as is wanted for
+/// aligned or gapped FASTA.
+///
+/// # Example
+///
+/// ```
+/// use faiquery::CompositionOpts;
+///
+/// let opts = CompositionOpts::default();
+/// assert!(!opts.skip_gaps);
+/// assert_eq!(opts.gap_bytes, b"-.*");
+///
+/// // Only treat `-` as a gap
+/// let opts = CompositionOpts {
+///     skip_gaps: true,
+///     gap_bytes: b"-".to_vec(),
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositionOpts {
+    /// Exclude gap bytes from the counts.
+    ///
+    /// Defaults to `false`.
+    pub skip_gaps: bool,
+    /// The bytes treated as gaps.
+    ///
+    /// Defaults to `-`, `.`, and `*`.
+    pub gap_bytes: Vec<u8>,
+}
+impl Default for CompositionOpts {
+    fn default() -> Self {
+        Self {
+            skip_gaps: false,
+            gap_bytes: b"-.*".to_vec(),
+        }
+    }
+}
+impl CompositionOpts {
+    /// Returns the default options with `skip_gaps` enabled.
+    pub fn skip_gaps() -> Self {
+        Self {
+            skip_gaps: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns `true` if the byte is excluded from the counts.
+    pub(crate) fn is_skipped(&self, c: u8) -> bool {
+        self.skip_gaps && self.gap_bytes.contains(&c)
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+/// The recency and size bookkeeping of a bounded cache of loaded contigs.
+///
+/// The sequences themselves are held by the `IndexedFasta`; this only decides which
+/// contigs to evict so that the cached bytes stay within `max_bytes`.
+#[derive(Debug)]
+pub(crate) struct ContigCache {
+    max_bytes: usize,
+    bytes: usize,
+    /// Cached contigs and their sizes, from least to most recently used.
+    order: VecDeque<(String, usize)>,
+}
+impl ContigCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            bytes: 0,
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Returns `true` if a contig of `len` bytes can be cached at all.
+    pub fn fits(&self, len: usize) -> bool {
+        len <= self.max_bytes
+    }
+
+    /// Marks a cached contig as the most recently used.
+    pub fn touch(&mut self, name: &str) {
+        if let Some(idx) = self.order.iter().position(|(n, _)| n == name) {
+            if let Some(item) = self.order.remove(idx) {
+                self.order.push_back(item);
+            }
+        }
+    }
+
+    /// Records a newly cached contig and returns the names of the contigs to evict.
+    ///
+    /// The least recently used contigs are evicted until the cached bytes are within
+    /// `max_bytes`, but the new contig itself is never evicted.
+    pub fn insert(&mut self, name: &str, len: usize) -> Vec<String> {
+        self.remove(name);
+        self.order.push_back((name.to_string(), len));
+        self.bytes += len;
+        let mut evicted = Vec::new();
+        while self.bytes > self.max_bytes && self.order.len() > 1 {
+            if let Some((name, len)) = self.order.pop_front() {
+                self.bytes -= len;
+                evicted.push(name);
+            }
+        }
+        evicted
+    }
+
+    /// Forgets a contig that is no longer cached.
+    pub fn remove(&mut self, name: &str) {
+        if let Some(idx) = self.order.iter().position(|(n, _)| n == name) {
+            if let Some((_, len)) = self.order.remove(idx) {
+                self.bytes -= len;
+            }
+        }
+    }
+
+    /// Forgets every contig.
+    pub fn clear(&mut self) {
+        self.order.clear();
+        self.bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::ContigCache;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = ContigCache::new(100);
+        assert!(cache.insert("a", 40).is_empty());
+        assert!(cache.insert("b", 40).is_empty());
+        cache.touch("a");
+        assert_eq!(cache.insert("c", 40), vec!["b"]);
+        assert_eq!(cache.insert("d", 90), vec!["a", "c"]);
+        assert!(!cache.fits(101));
+
+        cache.remove("d");
+        assert!(cache.insert("e", 100).is_empty());
+        cache.clear();
+        assert!(cache.insert("f", 100).is_empty());
+    }
+}
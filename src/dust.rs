@@ -0,0 +1,38 @@
+/// Computes the DUST low-complexity score of a sequence.
+///
+/// This is the SDUST triplet score `sum(c_t * (c_t - 1) / 2) / (l - 1)`, where `c_t` is
+/// the number of occurrences of triplet `t` and `l` is the number of triplets.
+/// Bases are case-folded and triplets containing a base other than `ACGT` are skipped.
+/// Sequences with fewer than two triplets score `0.0`.
+pub(crate) fn dust_score(seq: &[u8]) -> f64 {
+    let mut counts = [0usize; 64];
+    let mut num_triplets = 0;
+    for window in seq.windows(3) {
+        let triplet = window
+            .iter()
+            .try_fold(0, |acc, &c| encode(c).map(|code| (acc << 2) | code));
+        if let Some(triplet) = triplet {
+            counts[triplet] += 1;
+            num_triplets += 1;
+        }
+    }
+    if num_triplets < 2 {
+        return 0.0;
+    }
+    let sum = counts
+        .iter()
+        .map(|&c| c * c.saturating_sub(1) / 2)
+        .sum::<usize>();
+    sum as f64 / (num_triplets - 1) as f64
+}
+
+/// Encodes a nucleotide as a 2-bit code.
+fn encode(base: u8) -> Option<usize> {
+    match base.to_ascii_uppercase() {
+        b'A' => Some(0),
+        b'C' => Some(1),
+        b'G' => Some(2),
+        b'T' => Some(3),
+        _ => None,
+    }
+}
@@ -1,53 +1,551 @@
-use crate::IndexEntry;
-use anyhow::Result;
+use crate::{
+    bgzf::scan_blocks,
+    fasta_scanner::scan_fasta,
+    intervals::merge_intervals,
+    query_position::{validate_interval, QueryPosition},
+    IndexEntry,
+};
+use anyhow::{bail, Result};
+use flate2::read::MultiGzDecoder;
 use hashbrown::HashMap;
-use std::{fs::File, io::Read};
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, Read},
+};
 
 /// A FASTA index.
 ///
 /// This struct builds a map of FASTA entry names to their corresponding
-/// `IndexEntry` structs.
-#[derive(Debug)]
+/// `IndexEntry` structs and records the order in which they were inserted.
+///
+/// Two indexes are equal if they have the same entries in the same order.
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct FastaIndex {
     entries: HashMap<String, IndexEntry>,
+    order: Vec<String>,
 }
 impl FastaIndex {
-    /// Creates a new empty `FastaIndex`.
+    /// Creates a new empty `FastaIndex` (equivalent to `FastaIndex::default()`).
     pub fn new() -> Self {
         Self {
             entries: HashMap::new(),
+            order: Vec::new(),
         }
     }
     /// Inserts an `IndexEntry` into the `FastaIndex`.
-    pub fn insert(&mut self, entry: IndexEntry) {
-        self.entries.insert(entry.name.clone(), entry);
+    ///
+    /// An entry replacing one of the same name keeps the position of the original.
+    ///
+    /// # Errors
+    ///
+    /// Error if the line geometry of the entry is impossible, as in `IndexEntry::new`.
+    /// The fields of an `IndexEntry` are public, so an entry built directly from them is
+    /// checked here before it can be queried.
+    pub fn insert(&mut self, entry: IndexEntry) -> Result<()> {
+        entry.check_geometry()?;
+        let name = entry.name.clone();
+        if self.entries.insert(name.clone(), entry).is_none() {
+            self.order.push(name);
+        }
+        Ok(())
+    }
+    /// Moves all entries of `other` into this index, after the existing entries.
+    ///
+    /// The entries are kept exactly as they are, including their byte offsets, so
+    /// merging only makes sense if both indexes describe the same underlying FASTA file
+    /// (e.g. a genome and spike-in contigs that were indexed separately but concatenated
+    /// into one file with matching offsets).
+    ///
+    /// # Errors
+    ///
+    /// Error if any contig of `other` is already in the index, in which case the index
+    /// is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexEntry};
+    ///
+    /// let mut index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    /// let mut decoys = FastaIndex::new();
+    /// decoys
+    ///     .insert(IndexEntry {
+    ///         name: "decoy1".to_string(),
+    ///         length: 10,
+    ///         offset: 320,
+    ///         line_bases: 10,
+    ///         line_width: 11,
+    ///     })
+    ///     .unwrap();
+    /// index.extend(decoys).unwrap();
+    /// assert_eq!(index.len(), 3);
+    /// assert_eq!(index.get_nth(2).unwrap().name, "decoy1");
+    ///
+    /// let duplicate = FastaIndex::from_filepath("example_data/example.fa.fai").unwrap();
+    /// assert!(index.extend(duplicate).is_err());
+    /// ```
+    pub fn extend(&mut self, other: FastaIndex) -> Result<()> {
+        if let Some(name) = other
+            .order
+            .iter()
+            .find(|name| self.entries.contains_key(*name))
+        {
+            bail!("Cannot merge indexes: {} is in both", name);
+        }
+        let FastaIndex { mut entries, order } = other;
+        for name in order {
+            if let Some(entry) = entries.remove(&name) {
+                self.insert(entry)?;
+            }
+        }
+        Ok(())
+    }
+    /// Creates a new `FastaIndex` from the contents of an index file held in memory.
+    ///
+    /// This is useful when the index is embedded in the binary or received over the
+    /// network rather than read from disk. It is equivalent to `from_reader(data)`, and
+    /// any other source implementing `Read` (e.g. a decompressor or a socket) can be
+    /// passed to `from_reader` directly.
+    ///
+    /// # Errors
+    ///
+    /// All errors of `from_reader`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::FastaIndex;
+    ///
+    /// let index = FastaIndex::from_bytes(b"chr1\t112\t6\t28\t29\nchr2\t176\t128\t28\t29\n")
+    ///     .expect("Could not parse index");
+    /// assert_eq!(index.len(), 2);
+    /// assert_eq!(index.get("chr2").unwrap().offset, 128);
+    /// ```
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Self::from_reader(data)
     }
     /// Creates a new `FastaIndex` from a `Read` object.
+    ///
+    /// Blank lines and lines beginning with `#` are skipped, since some tools append
+    /// comments or trailing empty lines to index files.
+    ///
+    /// # Errors
+    ///
+    /// Errors name the offending line of the input.
+    ///
+    /// - Error if the input cannot be read.
+    /// - Error if any remaining line is not a valid five-field index row.
+    /// - Error if a row with sequence has an impossible line geometry: zero bases per
+    ///   line, a line width less than its line bases, or a line terminator longer than
+    ///   two bytes.
+    /// - Error if a name appears on more than one row, since only one of the rows could
+    ///   be queried.
     pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        let mut rows = String::new();
+        let mut line_numbers = Vec::new();
+        for (idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            rows.push_str(&line);
+            rows.push('\n');
+            line_numbers.push(idx + 1);
+        }
         let mut csv_reader = csv::ReaderBuilder::new()
             .delimiter(b'\t')
             .has_headers(false)
-            .from_reader(reader);
+            .from_reader(rows.as_bytes());
         let mut index = Self::new();
-        for record in csv_reader.deserialize() {
-            let record: IndexEntry = record?;
-            index.insert(record);
+        for (record, line_number) in csv_reader.deserialize().zip(line_numbers) {
+            let (name, length, offset, line_bases, line_width): (String, _, _, _, _) = match record
+            {
+                Ok(record) => record,
+                Err(e) => bail!("Invalid index entry on line {}: {}", line_number, e),
+            };
+            let record = IndexEntry::new(&name, length, offset, line_bases, line_width);
+            index.insert_row(record, line_number)?;
         }
         Ok(index)
     }
+    /// Creates a new `FastaIndex` from a `Read` object, tolerating whitespace quirks.
+    ///
+    /// This accepts index files that are not strictly tab-delimited: fields may be
+    /// separated by any run of spaces and tabs, and leading or trailing whitespace
+    /// (including the `\r` of a CRLF index) is ignored. Every row must still have
+    /// exactly five fields, so contig names containing whitespace cannot be read.
+    /// Blank lines and lines beginning with `#` are skipped and rows are validated as in
+    /// `from_reader`, which remains the strict default.
+    ///
+    /// # Errors
+    ///
+    /// Errors name the offending line of the input.
+    ///
+    /// - Error if the input cannot be read.
+    /// - Error if a row does not have exactly five fields or a numeric field is invalid.
+    /// - Error if a row is rejected by `from_reader` (impossible line geometry or a
+    ///   duplicate name).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::FastaIndex;
+    ///
+    /// let fai = "chr1  112 6\t28 29\r\nchr2\t176\t128\t28\t29\r\n";
+    /// let index = FastaIndex::from_reader_lenient(fai.as_bytes()).unwrap();
+    /// assert_eq!(index.get("chr1").unwrap().line_width, 29);
+    /// assert_eq!(index.len(), 2);
+    /// ```
+    pub fn from_reader_lenient<R: Read>(reader: R) -> Result<Self> {
+        let mut index = Self::new();
+        for (idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let fields = trimmed.split_whitespace().collect::<Vec<_>>();
+            if fields.len() != 5 {
+                bail!(
+                    "Invalid index entry on line {}: expected 5 fields but found {}",
+                    idx + 1,
+                    fields.len()
+                );
+            }
+            let parse = |field: &str, label: &str| match field.parse::<usize>() {
+                Ok(value) => Ok(value),
+                Err(_) => bail!(
+                    "Invalid index entry on line {}: invalid {} {}",
+                    idx + 1,
+                    label,
+                    field
+                ),
+            };
+            let record = IndexEntry::new(
+                fields[0],
+                parse(fields[1], "length")?,
+                parse(fields[2], "offset")?,
+                parse(fields[3], "line bases")?,
+                parse(fields[4], "line width")?,
+            );
+            index.insert_row(record, idx + 1)?;
+        }
+        Ok(index)
+    }
+    /// Inserts a row parsed from line `line_number` of an index file, naming the line
+    /// if the row failed validation or duplicates an existing name.
+    fn insert_row(&mut self, record: Result<IndexEntry>, line_number: usize) -> Result<()> {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => bail!("Invalid index entry on line {}: {}", line_number, e),
+        };
+        if self.entries.contains_key(&record.name) {
+            bail!(
+                "Invalid index entry on line {}: duplicate name {}",
+                line_number,
+                record.name
+            );
+        }
+        self.insert(record)
+    }
     /// Creates a new `FastaIndex` from a file path.
     pub fn from_filepath(path: &str) -> Result<Self> {
         let file = File::open(path)?;
         Self::from_reader(file)
     }
+    /// Builds a `FastaIndex` and `.gzi` entries from a BGZF-compressed FASTA file.
+    ///
+    /// This mirrors `samtools faidx ref.fa.gz`: the FASTA is decompressed to build the
+    /// index of the *uncompressed* sequence, and the BGZF block offsets are collected.
+    ///
+    /// The offsets are returned flattened as `[compressed, uncompressed, ...]` pairs,
+    /// one pair for every block start except the first, which is exactly the layout of a
+    /// `.gzi` file after its leading entry count.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the file is not valid BGZF.
+    /// - Error if the FASTA has a header without a name, sequence before the first
+    ///   header, or inconsistent line lengths within a record.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::FastaIndex;
+    ///
+    /// let (index, offsets) = FastaIndex::from_fasta_gz("example_data/example.fa.gz")
+    ///     .expect("Could not index BGZF FASTA file");
+    /// assert_eq!(index.get("chr1").unwrap().length, 112);
+    /// assert_eq!(&offsets[..2], &[70, 64]);
+    /// ```
+    pub fn from_fasta_gz(path: &str) -> Result<(Self, Vec<u64>)> {
+        let data = fs::read(path)?;
+        let blocks = scan_blocks(&data)?;
+        let reader = BufReader::new(MultiGzDecoder::new(data.as_slice()));
+        let mut index = Self::new();
+        for entry in scan_fasta(reader)? {
+            index.insert(entry)?;
+        }
+        let offsets = blocks
+            .into_iter()
+            .flat_map(|(compressed, uncompressed)| [compressed, uncompressed])
+            .collect();
+        Ok((index, offsets))
+    }
+    /// Compares the declared length of every entry with the bases present in a FASTA file.
+    ///
+    /// The FASTA is scanned record by record and the bases of each are counted.
+    /// Returns `(name, declared, counted)` for every entry whose counts differ, in index
+    /// order; an entry with no record in the FASTA is counted as `0` bases.
+    /// An empty result means that the index and the FASTA agree.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the FASTA file cannot be read.
+    /// - Error if the FASTA has a header without a name, sequence before the first
+    ///   header, or inconsistent line lengths within a record.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::FastaIndex;
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    /// let mismatches = index
+    ///     .verify_lengths("example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    /// assert!(mismatches.is_empty());
+    /// ```
+    pub fn verify_lengths(&self, fasta_path: &str) -> Result<Vec<(String, usize, usize)>> {
+        let reader = BufReader::new(File::open(fasta_path)?);
+        let counted = scan_fasta(reader)?
+            .into_iter()
+            .map(|entry| (entry.name, entry.length))
+            .collect::<HashMap<_, _>>();
+        Ok(self
+            .order
+            .iter()
+            .map(|name| &self.entries[name])
+            .filter_map(|entry| {
+                let count = counted.get(&entry.name).copied().unwrap_or(0);
+                (count != entry.length).then(|| (entry.name.clone(), entry.length, count))
+            })
+            .collect())
+    }
     /// Returns a reference to the `IndexEntry` corresponding to the given name.
     pub fn get(&self, name: &str) -> Option<&IndexEntry> {
         self.entries.get(name)
     }
+    /// Returns a reference to the `IndexEntry` at 0-based position `i` in insertion order.
+    ///
+    /// For an index read with `from_reader` or `from_filepath` this is the order of the
+    /// `.fai` file, which is the order of the records in the FASTA file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::FastaIndex;
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    ///
+    /// assert_eq!(index.get_nth(0).unwrap().name, "chr1");
+    /// assert_eq!(index.get_nth(1).unwrap().name, "chr2");
+    /// assert!(index.get_nth(2).is_none());
+    /// ```
+    pub fn get_nth(&self, i: usize) -> Option<&IndexEntry> {
+        self.order.get(i).and_then(|name| self.entries.get(name))
+    }
+    /// Resolves an interval to its location in the FASTA file.
+    ///
+    /// Returns the byte `offset` of the first base of `start..end` and the number of raw
+    /// bytes (including newlines) spanning the interval, which is exactly the slice
+    /// returned by `IndexedFasta::query_buffer`. This lets callers perform their own I/O
+    /// (e.g. `pread` on a file that is not memory-mapped) using the same offset math.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the contig `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `IndexedFasta::query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::FastaIndex;
+    /// use std::{fs::File, io::{Read, Seek, SeekFrom}};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    /// let (offset, byte_len) = index.resolve("chr1", 0, 10).unwrap();
+    /// assert_eq!((offset, byte_len), (6, 10));
+    ///
+    /// let mut file = File::open("example_data/example.fa").unwrap();
+    /// let mut buffer = vec![0; byte_len];
+    /// file.seek(SeekFrom::Start(offset as u64)).unwrap();
+    /// file.read_exact(&mut buffer).unwrap();
+    /// assert_eq!(buffer, b"ACCTACGATC");
+    /// ```
+    pub fn resolve(&self, name: &str, start: usize, end: usize) -> Result<(usize, usize)> {
+        let entry = match self.get(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        validate_interval(entry, start, end, true)?;
+        let query_pos = QueryPosition::new(start, end, entry);
+        Ok((query_pos.pos, query_pos.buffer_size))
+    }
     /// Returns a reference to the internal `HashMap` of entries.
     pub fn get_entries(&self) -> &HashMap<String, IndexEntry> {
         &self.entries
     }
+    /// Returns an iterator over the names of all entries in insertion order.
+    ///
+    /// For an index read with `from_reader` this is the order of the index file, as in
+    /// `get_nth` and `names_matching`.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.order.iter().map(|name| name.as_str())
+    }
+    /// Returns the names of all entries matching a regular expression in file order.
+    ///
+    /// This requires the `regex` feature.
+    ///
+    /// # Errors
+    ///
+    /// Error if `pattern` is not a valid regular expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::FastaIndex;
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    ///
+    /// assert_eq!(index.names_matching("^chr").unwrap(), vec!["chr1", "chr2"]);
+    /// assert_eq!(index.names_matching("2$").unwrap(), vec!["chr2"]);
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn names_matching(&self, pattern: &str) -> Result<Vec<&str>> {
+        let regex = regex::Regex::new(pattern)?;
+        let mut entries = self
+            .entries
+            .values()
+            .filter(|entry| regex.is_match(&entry.name))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.offset);
+        Ok(entries
+            .into_iter()
+            .map(|entry| entry.name.as_str())
+            .collect())
+    }
+    /// Returns the number of entries in the `FastaIndex`.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Returns `true` if the `FastaIndex` contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Returns the number of bases covered by `intervals` in each window of a contig.
+    ///
+    /// The contig is tiled into non-overlapping windows of `window` bases (the last window
+    /// may be shorter) and the covered-base count of each window is returned in order.
+    /// Intervals are half-open `(start, end)` and may be given in any order;
+    /// overlapping intervals are merged so that no base is counted twice.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the contig `name` is not found in the index.
+    /// - Error if `window` is zero.
+    /// - Error if an interval has `start > end` or extends past the contig end.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::FastaIndex;
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    ///
+    /// // chr1 is 112bp: windows of 50, 50, and 12 bases
+    /// let coverage = index
+    ///     .coverage_windows("chr1", &[(40, 60), (100, 112)], 50)
+    ///     .unwrap();
+    /// assert_eq!(coverage, vec![10, 10, 12]);
+    /// ```
+    pub fn coverage_windows(
+        &self,
+        name: &str,
+        intervals: &[(usize, usize)],
+        window: usize,
+    ) -> Result<Vec<usize>> {
+        let entry = match self.get(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        if window == 0 {
+            bail!("Window size must be greater than zero");
+        }
+        for &(start, end) in intervals {
+            if start > end {
+                bail!("Interval start {} is greater than its end {}", start, end);
+            }
+            if end > entry.length {
+                bail!(
+                    "Interval end {} is beyond the length of {} ({})",
+                    end,
+                    name,
+                    entry.length
+                );
+            }
+        }
+
+        // Merging overlaps first ensures no base is counted twice
+        let mut coverage = vec![0; entry.length.div_ceil(window)];
+        for (start, end) in merge_intervals(intervals) {
+            if start >= end {
+                continue;
+            }
+            for (idx, count) in coverage
+                .iter_mut()
+                .enumerate()
+                .take((end - 1) / window + 1)
+                .skip(start / window)
+            {
+                let window_start = (idx * window).max(start);
+                let window_end = ((idx + 1) * window).min(end);
+                *count += window_end - window_start;
+            }
+        }
+        Ok(coverage)
+    }
+}
+
+/// Serializes the entries as a sequence in insertion order.
+///
+/// This requires the `serde` feature.
+#[cfg(feature = "serde")]
+impl serde::Serialize for FastaIndex {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.order.iter().map(|name| &self.entries[name]))
+    }
+}
+
+/// Deserializes a sequence of entries, inserting them in order.
+///
+/// This requires the `serde` feature.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FastaIndex {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut index = Self::new();
+        for entry in <Vec<IndexEntry> as serde::Deserialize>::deserialize(deserializer)? {
+            index.insert(entry).map_err(serde::de::Error::custom)?;
+        }
+        Ok(index)
+    }
 }
 
 #[cfg(test)]
@@ -62,4 +560,242 @@ mod testing {
         assert_eq!(index.get_entries().len(), 2);
         Ok(())
     }
+
+    #[test]
+    fn iterate_names() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        assert_eq!(index.names().collect::<Vec<_>>(), vec!["chr1", "chr2"]);
+        assert_eq!(index.len(), 2);
+        assert!(!index.is_empty());
+        assert!(FastaIndex::new().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn skip_comments_and_blank_lines() -> Result<()> {
+        let fai =
+            "# generated by some tool\nchr1\t112\t6\t28\t29\n\nchr2\t176\t128\t28\t29\n\n# end\n";
+        let index = FastaIndex::from_reader(fai.as_bytes())?;
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get("chr2").unwrap().offset, 128);
+
+        let malformed = "chr1\t112\t6\t28\t29\nchr2\t176\t128\n";
+        assert!(FastaIndex::from_reader(malformed.as_bytes()).is_err());
+        let malformed = "chr1\t112\t6\t28\tabc\n";
+        assert!(FastaIndex::from_reader(malformed.as_bytes()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn insertion_order() -> Result<()> {
+        let fai = "chrB\t10\t6\t10\t11\nchrA\t10\t23\t10\t11\nchrC\t10\t40\t10\t11\n";
+        let mut index = FastaIndex::from_reader(fai.as_bytes())?;
+        let names = (0..index.len())
+            .map(|i| index.get_nth(i).unwrap().name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["chrB", "chrA", "chrC"]);
+        assert_eq!(index.names().collect::<Vec<_>>(), names);
+
+        // Replacing an entry keeps its position
+        let replacement = index.get("chrA").unwrap().clone();
+        index.insert(replacement)?;
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get_nth(1).unwrap().name, "chrA");
+        assert!(index.get_nth(3).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_windows() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        // chr2 is 176bp: windows 0..50, 50..100, 100..150, 150..176
+        let coverage = index.coverage_windows("chr2", &[(45, 105), (0, 5), (170, 176)], 50)?;
+        assert_eq!(coverage, vec![10, 50, 5, 6]);
+
+        // Overlapping intervals are only counted once
+        let coverage = index.coverage_windows("chr2", &[(0, 30), (20, 60), (25, 26)], 50)?;
+        assert_eq!(coverage, vec![50, 10, 0, 0]);
+
+        assert_eq!(index.coverage_windows("chr2", &[], 100)?, vec![0, 0]);
+        assert!(index.coverage_windows("chr2", &[(0, 177)], 50).is_err());
+        assert!(index.coverage_windows("chr2", &[(10, 5)], 50).is_err());
+        assert!(index.coverage_windows("chr2", &[(0, 5)], 0).is_err());
+        assert!(index.coverage_windows("chr3", &[(0, 5)], 50).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn from_fasta_gz_matches_fai() -> Result<()> {
+        let plain = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let (index, offsets) = FastaIndex::from_fasta_gz("example_data/example.fa.gz")?;
+        assert_eq!(index.len(), plain.len());
+        for (name, expected) in plain.get_entries() {
+            let entry = index.get(name).unwrap();
+            assert_eq!(entry.length, expected.length);
+            assert_eq!(entry.offset, expected.offset);
+            assert_eq!(entry.line_bases, expected.line_bases);
+            assert_eq!(entry.line_width, expected.line_width);
+        }
+
+        // Matches the .gzi written by bgzip after its leading entry count
+        let gzi = std::fs::read("example_data/example.fa.gz.gzi")?;
+        let expected = gzi[8..]
+            .chunks(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(offsets, expected);
+
+        assert!(FastaIndex::from_fasta_gz("example_data/example.fa").is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn names_matching() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        assert_eq!(index.names_matching("chr")?, vec!["chr1", "chr2"]);
+        assert_eq!(index.names_matching("^chr1$")?, vec!["chr1"]);
+        assert!(index.names_matching("^scaffold")?.is_empty());
+        assert!(index.names_matching("chr(").is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let bytes = bincode::serialize(&index)?;
+        let decoded: FastaIndex = bincode::deserialize(&bytes)?;
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded.get_nth(0).unwrap().name, "chr1");
+        let entry = decoded.get("chr2").unwrap();
+        assert_eq!((entry.length, entry.offset), (176, 128));
+        assert_eq!(
+            decoded.resolve("chr2", 0, 10)?,
+            index.resolve("chr2", 0, 10)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn equality_and_extend() -> Result<()> {
+        let fai = "chr1\t112\t6\t28\t29\n";
+        let mut index = FastaIndex::from_reader(fai.as_bytes())?;
+        let other = FastaIndex::from_reader("chr2\t176\t128\t28\t29\n".as_bytes())?;
+        assert_ne!(index, FastaIndex::from_filepath(TEST_FASTA_INDEX)?);
+        index.extend(other)?;
+        assert_eq!(index, FastaIndex::from_filepath(TEST_FASTA_INDEX)?);
+
+        // A duplicate name leaves the index unchanged
+        let other =
+            FastaIndex::from_reader("chr3\t10\t320\t10\t11\nchr1\t1\t1\t1\t2\n".as_bytes())?;
+        assert!(index.extend(other).is_err());
+        assert_eq!(index, FastaIndex::from_filepath(TEST_FASTA_INDEX)?);
+        Ok(())
+    }
+
+    #[test]
+    fn verify_lengths() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        assert!(index.verify_lengths("example_data/example.fa")?.is_empty());
+
+        let wrong = "chr1\t112\t6\t28\t29\nchr2\t180\t128\t28\t29\nchr3\t10\t400\t28\t29\n";
+        let index = FastaIndex::from_reader(wrong.as_bytes())?;
+        assert_eq!(
+            index.verify_lengths("example_data/example.fa")?,
+            vec![("chr2".to_string(), 180, 176), ("chr3".to_string(), 10, 0)]
+        );
+        assert!(index.verify_lengths("example_data/missing.fa").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_impossible_geometry() {
+        let cases = [
+            (
+                "chr1\t112\t6\t0\t29\n",
+                "line 1: chr1 has zero bases per line",
+            ),
+            (
+                "chr1\t112\t6\t28\t29\n\nchr2\t176\t128\t29\t28\n",
+                "line 3: chr2 has a line width (28) less than its line bases (29)",
+            ),
+            (
+                "# comment\nchr1\t112\t6\t28\t32\n",
+                "line 2: chr1 has a line terminator of 4 bytes",
+            ),
+            ("chr1\t112\t6\t28\n", "line 1: "),
+            (
+                "chr1\t112\t6\t28\t29\nchr2\tmany\t128\t28\t29\n",
+                "line 2: ",
+            ),
+        ];
+        for (fai, expected) in cases {
+            let err = FastaIndex::from_reader(fai.as_bytes()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}", err);
+        }
+
+        // Contigs without sequence have no line geometry
+        let mut index = FastaIndex::from_reader("empty\t0\t6\t0\t0\n".as_bytes()).unwrap();
+        assert_eq!(index.get("empty").unwrap().length, 0);
+
+        // Entries built from their public fields are checked on insert
+        let entry = crate::IndexEntry {
+            name: "chr1".to_string(),
+            length: 112,
+            offset: 6,
+            line_bases: 0,
+            line_width: 29,
+        };
+        let err = index.insert(entry).unwrap_err();
+        assert_eq!(err.to_string(), "chr1 has zero bases per line");
+        assert!(index.get("chr1").is_none());
+    }
+
+    #[test]
+    fn reject_duplicate_names() {
+        let fai = "chr1\t112\t6\t28\t29\nchr2\t176\t128\t28\t29\nchr1\t10\t400\t28\t29\n";
+        let err = FastaIndex::from_reader(fai.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid index entry on line 3: duplicate name chr1"
+        );
+    }
+
+    #[test]
+    fn from_reader_lenient() -> Result<()> {
+        let strict = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+
+        let crlf = "chr1\t112\t6\t28\t29\r\nchr2\t176\t128\t28\t29\r\n";
+        assert_eq!(FastaIndex::from_reader_lenient(crlf.as_bytes())?, strict);
+
+        let spaced = "# spaces\nchr1 112 6 28 29\n\n  chr2 \t 176   128\t28 29  \n";
+        assert_eq!(FastaIndex::from_reader_lenient(spaced.as_bytes())?, strict);
+        assert!(FastaIndex::from_reader(spaced.as_bytes()).is_err());
+
+        let cases = [
+            ("chr1 112 6 28\n", "line 1: expected 5 fields but found 4"),
+            (
+                "chr1 112 6 28 29 30\n",
+                "line 1: expected 5 fields but found 6",
+            ),
+            (
+                "chr1 112 6 28 29\nchr2 lots 128 28 29\n",
+                "line 2: invalid length lots",
+            ),
+            (
+                "chr1 112 6 28 32\n",
+                "line 1: chr1 has a line terminator of 4 bytes",
+            ),
+            (
+                "chr1 112 6 28 29\nchr1 112 6 28 29\n",
+                "line 2: duplicate name chr1",
+            ),
+        ];
+        for (fai, expected) in cases {
+            let err = FastaIndex::from_reader_lenient(fai.as_bytes()).unwrap_err();
+            assert!(err.to_string().contains(expected), "{}", err);
+        }
+        Ok(())
+    }
 }
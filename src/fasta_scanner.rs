@@ -0,0 +1,82 @@
+use crate::IndexEntry;
+use anyhow::{bail, Result};
+use std::io::BufRead;
+
+/// Scans a FASTA stream and builds an `IndexEntry` for every record.
+///
+/// The name of a record is the first whitespace-delimited word of its header.
+/// Line geometry is taken from the first sequence line of each record, and every
+/// line but the last must have the same number of bases (as required by the FAI format).
+/// Both `\n` and `\r\n` line endings are supported.
+pub(crate) fn scan_fasta<R: BufRead>(mut reader: R) -> Result<Vec<IndexEntry>> {
+    let mut entries = Vec::new();
+    let mut current: Option<IndexEntry> = None;
+    let mut last_line_short = false;
+    let mut offset = 0;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let num_bytes = reader.read_until(b'\n', &mut line)?;
+        if num_bytes == 0 {
+            break;
+        }
+        offset += num_bytes;
+
+        if line[0] == b'>' {
+            if let Some(entry) = current.take() {
+                entries.push(entry);
+            }
+            let name = trim_line_ending(&line[1..])
+                .split(|c| c.is_ascii_whitespace())
+                .next()
+                .unwrap_or_default();
+            if name.is_empty() {
+                bail!(
+                    "Found a header without a name at byte {}",
+                    offset - num_bytes
+                );
+            }
+            current = Some(IndexEntry {
+                name: String::from_utf8(name.to_vec())?,
+                length: 0,
+                offset,
+                line_bases: 0,
+                line_width: 0,
+            });
+            last_line_short = false;
+            continue;
+        }
+
+        let entry = match current.as_mut() {
+            Some(entry) => entry,
+            None => bail!(
+                "Found sequence before the first header at byte {}",
+                offset - num_bytes
+            ),
+        };
+        let bases = trim_line_ending(&line).len();
+        if bases == 0 {
+            last_line_short = true;
+            continue;
+        }
+        if last_line_short || (entry.line_bases > 0 && bases > entry.line_bases) {
+            bail!("Inconsistent line lengths in {}", entry.name);
+        }
+        if entry.line_bases == 0 {
+            entry.line_bases = bases;
+            entry.line_width = num_bytes;
+        }
+        last_line_short = bases < entry.line_bases;
+        entry.length += bases;
+    }
+    if let Some(entry) = current {
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Removes a trailing `\n` or `\r\n` from a line.
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
@@ -0,0 +1,161 @@
+use crate::{FastaIndex, IndexEntry};
+use anyhow::{bail, Result};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+};
+
+/// A FASTQ index.
+///
+/// This is the six-column index written by `samtools fqidx` (or `samtools faidx` on a
+/// FASTQ file): the five columns of a FASTA index followed by the offset of the first
+/// quality character of each read. The quality of a read is wrapped with the same line
+/// geometry as its sequence, so each read is kept as two `IndexEntry` values that only
+/// differ in their offset.
+///
+/// # Example
+///
+/// ```
+/// use faiquery::FastqIndex;
+///
+/// let index = FastqIndex::from_filepath("example_data/example.fq.fai")
+///     .expect("Could not read index file");
+/// assert_eq!(index.len(), 3);
+/// assert_eq!(index.get("read2").unwrap().offset, 38);
+/// assert_eq!(index.get_qual("read2").unwrap().offset, 57);
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FastqIndex {
+    seq: FastaIndex,
+    qual: FastaIndex,
+}
+impl FastqIndex {
+    /// Creates a new empty `FastqIndex`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Creates a new `FastqIndex` from a `Read` object.
+    ///
+    /// Blank lines and lines beginning with `#` are skipped, as in
+    /// `FastaIndex::from_reader`.
+    ///
+    /// # Errors
+    ///
+    /// Errors name the offending line of the input.
+    ///
+    /// - Error if the input cannot be read.
+    /// - Error if any remaining line is not a valid six-field index row.
+    /// - Error if a row is rejected by `IndexEntry::new` (impossible line geometry).
+    /// - Error if a name appears on more than one row.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        let mut index = Self::new();
+        for (idx, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let trimmed = line.trim_end_matches('\r');
+            if trimmed.trim().is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let fields = trimmed.split('\t').collect::<Vec<_>>();
+            if fields.len() != 6 {
+                bail!(
+                    "Invalid index entry on line {}: expected 6 fields but found {}",
+                    idx + 1,
+                    fields.len()
+                );
+            }
+            let parse = |field: &str, label: &str| match field.parse::<usize>() {
+                Ok(value) => Ok(value),
+                Err(_) => bail!(
+                    "Invalid index entry on line {}: invalid {} {}",
+                    idx + 1,
+                    label,
+                    field
+                ),
+            };
+            let length = parse(fields[1], "length")?;
+            let line_bases = parse(fields[3], "line bases")?;
+            let line_width = parse(fields[4], "line width")?;
+            let seq = match IndexEntry::new(
+                fields[0],
+                length,
+                parse(fields[2], "offset")?,
+                line_bases,
+                line_width,
+            ) {
+                Ok(entry) => entry,
+                Err(e) => bail!("Invalid index entry on line {}: {}", idx + 1, e),
+            };
+            if index.seq.get(&seq.name).is_some() {
+                bail!(
+                    "Invalid index entry on line {}: duplicate name {}",
+                    idx + 1,
+                    seq.name
+                );
+            }
+            let qual = IndexEntry {
+                offset: parse(fields[5], "quality offset")?,
+                ..seq.clone()
+            };
+            index.seq.insert(seq)?;
+            index.qual.insert(qual)?;
+        }
+        Ok(index)
+    }
+    /// Creates a new `FastqIndex` from a file path.
+    pub fn from_filepath(path: &str) -> Result<Self> {
+        let file = File::open(path)?;
+        Self::from_reader(file)
+    }
+    /// Creates a new `FastqIndex` from the contents of an index file held in memory.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Self::from_reader(data)
+    }
+    /// Returns the `IndexEntry` describing the sequence of a read.
+    pub fn get(&self, name: &str) -> Option<&IndexEntry> {
+        self.seq.get(name)
+    }
+    /// Returns the `IndexEntry` describing the quality of a read.
+    ///
+    /// This is the entry of the sequence with the offset of the quality string.
+    pub fn get_qual(&self, name: &str) -> Option<&IndexEntry> {
+        self.qual.get(name)
+    }
+    /// Returns the names of the reads in the order of the index file.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        (0..self.seq.len()).filter_map(|i| self.seq.get_nth(i).map(|entry| entry.name.as_str()))
+    }
+    /// Returns the number of reads in the index.
+    pub fn len(&self) -> usize {
+        self.seq.len()
+    }
+    /// Returns `true` if the index has no reads.
+    pub fn is_empty(&self) -> bool {
+        self.seq.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::FastqIndex;
+
+    #[test]
+    fn parse_rows() {
+        let index =
+            FastqIndex::from_bytes(b"r1\t10\t4\t10\t11\t18\n\nr2\t4\t34\t4\t5\t41\n").unwrap();
+        assert_eq!(index.names().collect::<Vec<_>>(), vec!["r1", "r2"]);
+        let qual = index.get_qual("r1").unwrap();
+        assert_eq!((qual.length, qual.offset, qual.line_width), (10, 18, 11));
+        assert!(index.get("r3").is_none());
+    }
+
+    #[test]
+    fn invalid_rows() {
+        // FASTA index rows have no quality offset
+        assert!(FastqIndex::from_bytes(b"r1\t10\t4\t10\t11\n").is_err());
+        assert!(FastqIndex::from_bytes(b"r1\t10\t4\t10\t11\tx\n").is_err());
+        assert!(FastqIndex::from_bytes(b"r1\t10\t4\t0\t1\t18\n").is_err());
+        let err =
+            FastqIndex::from_bytes(b"r1\t10\t4\t10\t11\t18\nr1\t10\t4\t10\t11\t18\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+}
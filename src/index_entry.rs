@@ -1,3 +1,4 @@
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
 
 /// A FASTA index entry.
@@ -6,7 +7,11 @@ use serde::{Deserialize, Serialize};
 /// It contains the name of the entry, the length of the entry,
 /// the offset of the entry in the FASTA file, and the line
 /// width and line bases of the entry.
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// The fields are public for compatibility, but an entry built directly from them is
+/// not validated and an impossible line geometry will produce incorrect queries.
+/// Prefer `IndexEntry::new`, which checks the geometry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct IndexEntry {
     pub name: String,
     pub length: usize,
@@ -14,3 +19,82 @@ pub struct IndexEntry {
     pub line_bases: usize,
     pub line_width: usize,
 }
+impl IndexEntry {
+    /// Creates a new `IndexEntry`, checking that its line geometry is possible.
+    ///
+    /// Every line holds `line_bases` bases followed by a line terminator of
+    /// `line_width - line_bases` bytes. An entry without sequence (`length == 0`) is
+    /// accepted with any geometry, since samtools writes such entries with zeroed line
+    /// fields.
+    ///
+    /// # Errors
+    ///
+    /// - Error if `line_bases` is zero.
+    /// - Error if `line_width` is less than `line_bases`.
+    /// - Error if the line terminator is longer than two bytes (`\r\n`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::IndexEntry;
+    ///
+    /// let entry = IndexEntry::new("chr1", 112, 6, 28, 29).unwrap();
+    /// assert_eq!(entry.line_width, 29);
+    ///
+    /// assert!(IndexEntry::new("chr1", 112, 6, 0, 1).is_err());
+    /// assert!(IndexEntry::new("chr1", 112, 6, 28, 27).is_err());
+    /// assert!(IndexEntry::new("chr1", 112, 6, 28, 31).is_err());
+    ///
+    /// // an empty contig is accepted with zeroed line fields
+    /// assert!(IndexEntry::new("empty", 0, 29, 0, 0).is_ok());
+    /// ```
+    pub fn new(
+        name: &str,
+        length: usize,
+        offset: usize,
+        line_bases: usize,
+        line_width: usize,
+    ) -> Result<Self> {
+        let entry = Self {
+            name: name.to_string(),
+            length,
+            offset,
+            line_bases,
+            line_width,
+        };
+        entry.check_geometry()?;
+        Ok(entry)
+    }
+
+    /// Checks that the line geometry of the entry is possible.
+    ///
+    /// Every line holds `line_bases > 0` bases followed by a terminator of at most two
+    /// bytes (`\n` or `\r\n`), so `line_width - line_bases` must be `0..=2`.
+    /// The geometry of an entry without sequence (`length == 0`) is not checked.
+    pub(crate) fn check_geometry(&self) -> Result<()> {
+        if self.length == 0 {
+            return Ok(());
+        }
+        if self.line_bases == 0 {
+            bail!("{} has zero bases per line", self.name);
+        }
+        if self.line_width < self.line_bases {
+            bail!(
+                "{} has a line width ({}) less than its line bases ({})",
+                self.name,
+                self.line_width,
+                self.line_bases
+            );
+        }
+        if self.line_width - self.line_bases > 2 {
+            bail!(
+                "{} has a line terminator of {} bytes (line width {}, line bases {})",
+                self.name,
+                self.line_width - self.line_bases,
+                self.line_width,
+                self.line_bases
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,33 @@
-use crate::{FastaIndex, IndexEntry};
-use anyhow::{bail, Result};
+use crate::{
+    base_counts::BaseCounts,
+    bed::parse_bed_line,
+    checksum::{checksum, ChecksumAlgo},
+    complement::{ensure_nucleotide, DNA_COMPLEMENT},
+    composition_opts::CompositionOpts,
+    contig_cache::ContigCache,
+    dust::dust_score,
+    intervals::merge_intervals,
+    line_ending::{extend_stripped, is_line_break},
+    query_position::{validate_bounds, validate_interval, LineGeometry, QueryPosition},
+    region::parse_region,
+    storage::Storage,
+    translate::translate,
+    Advice, Backend, FastaIndex, IndexEntry, IndexedFastaBuilder, IntervalMode, IntervalStore,
+    LineEnding, RecordCursor, RegionCheck, Sequence, Strand, WindowCursor,
+};
+use anyhow::{anyhow, bail, Result};
+use hashbrown::HashMap;
 use memmap2::Mmap;
-use std::fs::File;
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{BufRead, BufReader, Read, Write},
+    ops::{Bound, Range, RangeBounds},
+    sync::Mutex,
+};
+
+/// The number of bases written at a time by `write_region_revcomp`.
+const REVCOMP_CHUNK_SIZE: usize = 8192;
 
 /// An indexed FASTA file.
 ///
@@ -26,102 +52,258 @@ use std::fs::File;
 #[derive(Debug)]
 pub struct IndexedFasta {
     index: FastaIndex,
-    map: Mmap,
+    storage: Storage,
     buffer: Vec<u8>,
+    offsets: HashMap<String, isize>,
+    last: Option<(String, LineGeometry)>,
+    uppercase: bool,
+    loaded: HashMap<String, Vec<u8>>,
+    aliases: HashMap<String, String>,
+    features: IntervalStore,
+    cache: Option<ContigCache>,
 }
 impl IndexedFasta {
     /// Create a new `IndexedFasta` from a `FastaIndex` and a file path.
     pub fn new(index: FastaIndex, path: &str) -> Result<Self> {
-        let file = File::open(path)?;
-        let mmap = unsafe { Mmap::map(&file)? };
-        let buffer = Vec::new();
-        Ok(Self {
+        Self::builder(index, path).build()
+    }
+
+    /// Create a new `IndexedFasta` with an internal buffer preallocated to `capacity` bytes.
+    ///
+    /// The buffer is never shrunk by a query, so preallocating it for the largest
+    /// expected query avoids repeated reallocations when querying many large intervals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::with_buffer_capacity(index, "example_data/example.fa", 10_000)
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx.query("chr1", 0, 10).unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    /// ```
+    pub fn with_buffer_capacity(index: FastaIndex, path: &str, capacity: usize) -> Result<Self> {
+        Self::builder(index, path).buffer_capacity(capacity).build()
+    }
+
+    /// Create a new `IndexedFasta` that caches up to `max_bytes` of decoded contigs.
+    ///
+    /// `query` loads the whole contig it reads from (as `load_contig`) and slices later
+    /// queries of that contig from memory. Once the cached contigs exceed `max_bytes`
+    /// the least recently queried ones are unloaded, so repeated queries against a
+    /// working set of contigs run from memory while memory usage stays bounded.
+    /// A contig larger than `max_bytes` is never cached and is queried from the file.
+    ///
+    /// Contigs loaded with `load_contig` count towards the same limit and may be
+    /// evicted like any other cached contig.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    /// // chr1 is 112bp and chr2 is 176bp so only one fits
+    /// let mut faidx = IndexedFasta::with_cache(index, "example_data/example.fa", 200)
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// assert_eq!(faidx.query("chr1", 0, 10).unwrap(), b"ACCTACGATC");
+    /// assert!(faidx.is_loaded("chr1"));
+    ///
+    /// assert_eq!(faidx.query("chr2", 0, 10).unwrap(), b"TTTTGATCGA");
+    /// assert!(faidx.is_loaded("chr2"));
+    /// assert!(!faidx.is_loaded("chr1"));
+    /// ```
+    pub fn with_cache(index: FastaIndex, path: &str, max_bytes: usize) -> Result<Self> {
+        let mut faidx = Self::new(index, path)?;
+        faidx.cache = Some(ContigCache::new(max_bytes));
+        Ok(faidx)
+    }
+
+    /// Returns an `IndexedFastaBuilder` to configure the backend and query behavior.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{Backend, FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::builder(index, "example_data/example.fa")
+    ///     .backend(Backend::Mmap)
+    ///     .buffer_capacity(4096)
+    ///     .build()
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx.query("chr1", 0, 10).unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    /// ```
+    pub fn builder(index: FastaIndex, path: &str) -> IndexedFastaBuilder {
+        IndexedFastaBuilder::new(index, path)
+    }
+
+    /// Create an `IndexedFasta` from its storage and buffer settings.
+    pub(crate) fn from_storage(
+        index: FastaIndex,
+        storage: Storage,
+        buffer: Vec<u8>,
+        uppercase: bool,
+    ) -> Self {
+        Self {
             index,
-            map: mmap,
+            storage,
             buffer,
-        })
+            offsets: HashMap::new(),
+            last: None,
+            uppercase,
+            loaded: HashMap::new(),
+            aliases: HashMap::new(),
+            features: IntervalStore::new(),
+            cache: None,
+        }
     }
 
-    /// Validate the start and end positions of a query interval.
-    fn validate_interval(
-        &self,
-        entry: &IndexEntry,
-        start: usize,
-        end: usize,
-        bounded: bool,
-    ) -> Result<()> {
-        if start > end {
-            bail!("Start position must be less than end position");
-        } else if start == end {
-            bail!("Start and end positions must not be equal");
-        } else if start >= entry.length {
-            bail!("Start position must be less than sequence length");
-        } else if bounded && end > entry.length {
-            bail!("End position must be less than sequence length");
-        }
-        Ok(())
+    /// Create a new `IndexedFasta` from a `FastaIndex` and a BGZF-compressed FASTA file.
+    ///
+    /// This is the layout produced by `bgzip -i` (or `samtools faidx` on a `.fa.gz`)
+    /// where `gzi_path` is the `.gzi` block index and `path` is the compressed FASTA.
+    /// The `FastaIndex` describes the *uncompressed* file, exactly as for a plain FASTA.
+    ///
+    /// Queries translate the uncompressed offset of a region to its compressed block and
+    /// inflate only the blocks spanning the region.
+    /// Since the sequence must be decompressed the zero-copy `query_buffer` methods are
+    /// not supported and will return an error. Use the buffered `query` methods instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new_bgzf(
+    ///     index,
+    ///     "example_data/example.fa.gz.gzi",
+    ///     "example_data/example.fa.gz",
+    /// )
+    /// .expect("Could not read BGZF FASTA file");
+    ///
+    /// // Query the first 10 bases of chr2
+    /// let seq = faidx.query("chr2", 0, 10).unwrap();
+    /// assert_eq!(seq, b"TTTTGATCGA");
+    /// ```
+    pub fn new_bgzf(index: FastaIndex, gzi_path: &str, path: &str) -> Result<Self> {
+        Self::builder(index, path)
+            .backend(Backend::Bgzf {
+                gzi_path: gzi_path.to_string(),
+            })
+            .build()
     }
 
-    /// Query the FASTA file by name and position.
+    /// Create a new `IndexedFasta` that reads from an open file instead of a memory map.
     ///
-    /// The sequence is returned as a `&[u8]` slice but is not guaranteed to be valid UTF-8.
-    /// It also removes all newline characters from the sequence slice.
+    /// This is useful where memory-mapping fails or is discouraged (e.g. FIFOs or network
+    /// filesystems). Queries `seek` to the region and `read_exact` its bytes, so the
+    /// query API is identical to `new` except that the zero-copy `query_buffer` methods
+    /// are not supported and will return an error.
     ///
-    /// To query intervals that are potentially out of bounds, use `query_unbounded` and
-    /// return a truncated sequence use `query_unbounded`.
+    /// # Example
     ///
-    /// # Errors
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
     ///
-    /// - Error if the query `name`is not found in the index.
-    /// - Error if the `start` position is greater than the `end` position.
-    /// - Error if the `start` position is equal to the `end` position.
-    /// - Error if the `end` position is greater than the index sequence length.
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///     .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new_with_file(index, "example_data/example.fa")
+    ///     .expect("Could not open FASTA file");
+    ///
+    /// let seq = faidx.query("chr1", 0, 10).unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    /// assert!(faidx.query_buffer("chr1", 0, 10).is_err());
+    /// ```
+    pub fn new_with_file(index: FastaIndex, path: &str) -> Result<Self> {
+        Self::builder(index, path).backend(Backend::File).build()
+    }
+
+    /// Create a new `IndexedFasta` from a `FastaIndex` and an existing memory map.
+    ///
+    /// The map holds the plain-text FASTA file and is queried exactly as with `new`,
+    /// so no file path is needed: the map may come from a file opened elsewhere or from
+    /// an anonymous map filled in memory. Together with `FastaIndex::from_bytes` this
+    /// lets the whole pipeline run without touching the filesystem.
     ///
     /// # Example
     ///
     /// ```
     /// use faiquery::{FastaIndex, IndexedFasta};
+    /// use memmap2::MmapMut;
+    ///
+    /// let fasta = b">chr1\nACCTACGATC\nGACT\n";
+    /// let index = FastaIndex::from_bytes(b"chr1\t14\t6\t10\t11\n").unwrap();
+    ///
+    /// let mut map = MmapMut::map_anon(fasta.len()).unwrap();
+    /// map.copy_from_slice(fasta);
+    /// let mut faidx = IndexedFasta::from_mmap(index, map.make_read_only().unwrap());
+    ///
+    /// let seq = faidx.query("chr1", 8, 12).unwrap();
+    /// assert_eq!(seq, b"TCGA");
+    /// ```
+    pub fn from_mmap(index: FastaIndex, mmap: Mmap) -> Self {
+        Self::from_storage(index, Storage::Mmap(mmap), Vec::new(), false)
+    }
+
+    /// Advise the kernel of the expected access pattern of the FASTA file.
+    ///
+    /// Page-fault behavior has a large impact on throughput: a genome-wide linear pass
+    /// benefits from `Advice::Sequential` (aggressive read-ahead), while random region
+    /// lookups (e.g. driven by a BED file) benefit from `Advice::Random`, which avoids
+    /// reading pages that will never be used. The advice applies to the whole memory map.
+    ///
+    /// This wraps `madvise` and is a no-op on platforms without it or when the file is
+    /// not memory-mapped (see `new_with_file`).
+    ///
+    /// # Errors
+    ///
+    /// Error if the kernel rejects the advice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{Advice, FastaIndex, IndexedFasta};
     ///
     /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
     ///    .expect("Could not read index file");
     /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
     ///     .expect("Could not read FASTA file");
     ///
-    /// // Query a region from the middle of chr1
-    /// let seq = faidx.query("chr1", 50, 80).unwrap();
-    /// assert_eq!(seq.len(), 30);
-    ///
-    /// // Overextend the query into chr1 (which is 112 bases long)
-    /// let seq = faidx.query("chr1", 100, 120);
-    /// assert!(seq.is_err());
+    /// faidx.advise(Advice::Sequential).unwrap();
+    /// let seq = faidx.query_all("chr1").unwrap();
+    /// assert_eq!(seq.len(), 112);
     /// ```
-    pub fn query(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
-        let entry = match self.index.get(name) {
-            Some(entry) => entry,
-            None => bail!("No entry found for {}", name),
-        };
-        self.validate_interval(entry, start, end, true)?;
-        self.buffer.clear();
-        let query_pos = QueryPosition::new(start, end, entry);
-        let seq_slice = &self.map[query_pos.pos..query_pos.pos + query_pos.buffer_size];
-        self.buffer.extend_from_slice(seq_slice);
-        self.buffer.retain(|&c| c != b'\n');
-        Ok(&self.buffer)
+    pub fn advise(&self, advice: Advice) -> Result<()> {
+        self.storage.advise(advice)
     }
 
-    /// Query the FASTA file by name and position but do not copy to internal buffer.
+    /// Prefetch an interval so that a later query does not wait on I/O.
     ///
-    /// This will **not** remove newline characters from the sequence slice.
+    /// This advises the kernel that the pages spanning the interval will be needed soon
+    /// (`madvise` with `WillNeed`) so it can start reading them in the background, which
+    /// overlaps I/O with compute when the next region of a pipeline is known ahead of time.
     ///
-    /// This method is useful for memory-efficient operations where the sequence is not
-    /// necessarily needed as a `&str` slice (since newlines are potentially included).
+    /// The interval is validated as in `query`. Reading is not affected, and this is a
+    /// no-op on platforms without `madvise` or when the file is not an uncompressed
+    /// memory map.
     ///
     /// # Errors
     ///
-    /// - Error if the query `name` is not found in the index.
-    /// - Error if the `start` position is greater than the `end` position.
-    /// - Error if the `start` position is equal to the `end` position.
-    /// - Error if the `end` position is greater than the index sequence length.
+    /// See `query`.
+    ///
+    /// # Example
     ///
     /// ```
     /// use faiquery::{FastaIndex, IndexedFasta};
@@ -131,92 +313,122 @@ impl IndexedFasta {
     /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
     ///     .expect("Could not read FASTA file");
     ///
-    /// // Query a region from the middle of chr1 (note the newline character is included)
-    /// let seq = faidx.query_buffer("chr1", 50, 80).unwrap();
-    /// assert_eq!(seq.len(), 31);
-    /// assert!(seq.contains(&b'\n'));
-    /// assert_eq!(seq.iter().filter(|&&c| c != b'\n').count(), 30);
+    /// faidx.prefetch("chr2", 100, 176).unwrap();
+    /// // ... other work ...
+    /// let seq = faidx.query("chr2", 100, 176).unwrap();
+    /// assert_eq!(seq.len(), 76);
     ///
-    /// // Overextend the query into chr1 (which is 112 bases long)
-    /// let seq = faidx.query_buffer("chr1", 100, 120);
-    /// assert!(seq.is_err());
+    /// assert!(faidx.prefetch("chr2", 100, 200).is_err());
     /// ```
-    pub fn query_buffer(&self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
-        let entry = match self.index.get(name) {
+    pub fn prefetch(&self, name: &str, start: usize, end: usize) -> Result<()> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        let entry = match self.entry(name) {
             Some(entry) => entry,
             None => bail!("No entry found for {}", name),
         };
-        self.validate_interval(entry, start, end, true)?;
+        validate_interval(entry, start, end, true)?;
         let query_pos = QueryPosition::new(start, end, entry);
-        let seq_slice = &self.map[query_pos.pos..query_pos.pos + query_pos.buffer_size];
-        Ok(seq_slice)
+        self.storage
+            .prefetch(&query_pos)
+            .map_err(|e| read_error(name, e))
     }
 
-    /// Query the FASTA file by name and position.
+    /// Returns the sequence length of a contig or `None` if it is not in the index.
     ///
-    /// The sequence is returned as a `&[u8]` slice but is not guaranteed to be valid UTF-8.
-    /// It also removes all newline characters from the sequence slice.
+    /// # Example
     ///
-    /// This method will truncate the sequence if the `end` position is greater than the sequence length
-    /// to avoid an error and only return the sequence up to the sequence length.
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// assert_eq!(faidx.seq_len("chr1"), Some(112));
+    /// assert_eq!(faidx.seq_len("chr3"), None);
+    /// ```
+    pub fn seq_len(&self, name: &str) -> Option<usize> {
+        self.entry(name).map(|entry| entry.length)
+    }
+
+    /// Returns the full header line of a contig, including the `>` and any description.
+    ///
+    /// The `.fai` only stores the name of a contig (the first word of its header), so the
+    /// header line is recovered from the FASTA file: the sequence starts at `entry.offset`,
+    /// the line just before it is the header, and it is returned without its line
+    /// terminator.
+    ///
+    /// This borrows directly from the memory map like `query_buffer`.
     ///
     /// # Errors
     ///
-    /// - Error if the query `name`is not found in the index.
-    /// - Error if the `start` position is greater than the `end` position.
-    /// - Error if the `start` position is equal to the `end` position.
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the entry offset does not follow a header line.
+    /// - Error if the FASTA file is BGZF-compressed or read with `Backend::File`.
     ///
     /// # Example
     ///
     /// ```
     /// use faiquery::{FastaIndex, IndexedFasta};
     ///
-    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    /// let index = FastaIndex::from_filepath("example_data/protein.fa.fai")
     ///    .expect("Could not read index file");
-    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    /// let faidx = IndexedFasta::new(index, "example_data/protein.fa")
     ///     .expect("Could not read FASTA file");
     ///
-    /// // Overextend the query into chr1 (which is 112 bases long)
-    /// let seq = faidx.query("chr1", 100, 120);
-    /// assert!(seq.is_err());
-    ///
-    /// // Overextend the query into chr1 but truncate the sequence
-    /// // with `query_unbounded`
-    /// let seq = faidx.query_unbounded("chr1", 100, 120).unwrap();
-    /// assert_eq!(seq.len(), 12);
+    /// let header = faidx.header("sp|P0001|PROT1").unwrap();
+    /// assert_eq!(header, b">sp|P0001|PROT1 Example protein one");
     /// ```
-    pub fn query_unbounded(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
-        let entry = match self.index.get(name) {
+    pub fn header(&self, name: &str) -> Result<&[u8]> {
+        let entry = match self.entry(name) {
             Some(entry) => entry,
             None => bail!("No entry found for {}", name),
         };
-        self.validate_interval(entry, start, end, false)?;
-        let end = if end > entry.length {
-            entry.length
-        } else {
-            end
-        };
-        self.buffer.clear();
-        let query_pos = QueryPosition::new(start, end, entry);
-        let seq_slice = &self.map[query_pos.pos..query_pos.pos + query_pos.buffer_size];
-        self.buffer.extend_from_slice(seq_slice);
-        self.buffer.retain(|&c| c != b'\n');
-        Ok(&self.buffer)
+        let range = self.header_range(entry.offset)?;
+        let header = self.storage.slice(range.start, range.len())?;
+        if header.first() != Some(&b'>') {
+            bail!("offset {} does not follow a header line", entry.offset);
+        }
+        Ok(header)
     }
 
-    /// Query the FASTA file by name and position.
+    /// Returns the number of sequence bases in a validated interval.
     ///
-    /// The sequence is returned as a `&[u8]` slice but is not guaranteed to be valid UTF-8.
-    /// This will **not** remove newline characters from the sequence slice.
+    /// This is `end - start` once the interval has been validated as in `query`, and is
+    /// the length of the sequence returned by `query` (i.e. excluding newlines).
     ///
-    /// This method will truncate the sequence if the `end` position is greater than the sequence length
-    /// to avoid an error and only return the sequence up to the sequence length.
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// assert_eq!(faidx.region_len("chr1", 20, 40).unwrap(), 20);
+    /// assert!(faidx.region_len("chr1", 100, 120).is_err());
+    /// ```
+    pub fn region_len(&self, name: &str, start: usize, end: usize) -> Result<usize> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        self.index.resolve(self.canonical_name(name), start, end)?;
+        Ok(end - start)
+    }
+
+    /// Returns the number of raw bytes (including newlines) spanning a validated interval.
+    ///
+    /// This is the length of the slice returned by `query_buffer`, which lets callers
+    /// size their own buffers precisely.
     ///
     /// # Errors
     ///
-    /// - Error if the query `name`is not found in the index.
-    /// - Error if the `start` position is greater than the `end` position.
-    /// - Error if the `start` position is equal to the `end` position.
+    /// See `query`.
     ///
     /// # Example
     ///
@@ -225,59 +437,3624 @@ impl IndexedFasta {
     ///
     /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
     ///    .expect("Could not read index file");
-    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
     ///     .expect("Could not read FASTA file");
     ///
-    /// // Overextend the query into chr1 (which is 112 bases long)
-    /// let seq = faidx.query_buffer("chr1", 100, 120);
-    /// assert!(seq.is_err());
+    /// // The interval spans one newline
+    /// assert_eq!(faidx.raw_region_len("chr1", 20, 40).unwrap(), 21);
+    /// assert_eq!(faidx.query_buffer("chr1", 20, 40).unwrap().len(), 21);
+    /// ```
+    pub fn raw_region_len(&self, name: &str, start: usize, end: usize) -> Result<usize> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        let (_, byte_len) = self.index.resolve(self.canonical_name(name), start, end)?;
+        Ok(byte_len)
+    }
+
+    /// Returns `true` if the contig is present in the index.
     ///
-    /// // Overextend the query into chr1 but truncate the sequence
-    /// // with `query_unbounded`
-    /// let seq = faidx.query_buffer_unbounded("chr1", 100, 120).unwrap();
+    /// # Example
     ///
-    /// // The sequence is truncated to 13 characters
-    /// assert_eq!(seq.len(), 13);
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
     ///
-    /// // The sequence contains newline characters
-    /// assert!(seq.contains(&b'\n'));
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
     ///
-    /// // The sequence contains 12 non-newline characters
-    /// assert_eq!(seq.iter().filter(|&&c| c != b'\n').count(), 12);
+    /// assert!(faidx.contains("chr1"));
+    /// assert!(!faidx.contains("chr3"));
     /// ```
-    pub fn query_buffer_unbounded(&self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
-        let entry = match self.index.get(name) {
-            Some(entry) => entry,
-            None => bail!("No entry found for {}", name),
-        };
-        self.validate_interval(entry, start, end, false)?;
-        let end = if end > entry.length {
-            entry.length
-        } else {
-            end
-        };
-        let query_pos = QueryPosition::new(start, end, entry);
-        let seq_slice = &self.map[query_pos.pos..query_pos.pos + query_pos.buffer_size];
-        Ok(seq_slice)
+    pub fn contains(&self, name: &str) -> bool {
+        self.entry(name).is_some()
     }
-}
 
-/// A query position.
-///
-/// This struct is used to calculate the position of a query in a FASTA file.
-/// It is used to calculate the offset and size of the query in the memory-mapped file.
-struct QueryPosition {
-    pub buffer_size: usize,
-    pub pos: usize,
-}
-impl QueryPosition {
-    pub fn new(start: usize, end: usize, entry: &IndexEntry) -> Self {
-        let size = end - start;
-        let row_pos = (start / entry.line_bases) * entry.line_width;
-        let col_pos = start % entry.line_bases;
-        let num_lines = (size + col_pos) / entry.line_bases;
-        let buffer_size = size + num_lines;
-        let pos = entry.offset + row_pos + col_pos;
-        Self { buffer_size, pos }
+    /// Returns an iterator over the names of all contigs in the order of the index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let names = faidx.names().collect::<Vec<_>>();
+    /// assert_eq!(names, vec!["chr1", "chr2"]);
+    /// ```
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.index.names()
+    }
+
+    /// Returns the names of all contigs in the order they appear in the FASTA file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// assert_eq!(faidx.record_names_sorted(), vec!["chr1", "chr2"]);
+    /// ```
+    pub fn record_names_sorted(&self) -> Vec<String> {
+        self.ordered_entries()
+            .into_iter()
+            .map(|entry| entry.name.clone())
+            .collect()
     }
+
+    /// Create a cursor over the full sequence of every record in file order.
+    ///
+    /// The cursor is advanced with `RecordCursor::next_record`, which borrows this
+    /// `IndexedFasta` to read each record into the internal buffer. Records are visited
+    /// in on-disk order, which may differ from the order of the index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let mut cursor = faidx.records();
+    /// let mut lengths = Vec::new();
+    /// while let Some(record) = cursor.next_record(&mut faidx) {
+    ///     let (name, seq) = record.unwrap();
+    ///     lengths.push((name.to_string(), seq.len()));
+    /// }
+    /// assert_eq!(lengths, vec![("chr1".to_string(), 112), ("chr2".to_string(), 176)]);
+    /// ```
+    pub fn records(&self) -> RecordCursor {
+        RecordCursor::new(self.record_names_sorted())
+    }
+
+    /// Register a coordinate offset for a contig.
+    ///
+    /// All subsequent interval queries on `name` add `delta` to both the `start` and `end`
+    /// positions before validation. This is useful when the query coordinates live in a
+    /// lifted-over space with a known linear shift from the FASTA coordinates.
+    ///
+    /// Whole-contig methods such as `query_all` are not affected.
+    /// A query errors if the shifted interval would start or end below zero, and the
+    /// shifted interval is validated against the sequence length like any other query.
+    ///
+    /// Setting a `delta` of `0` removes the offset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Coordinates on chr1 are 100 bases ahead of the FASTA
+    /// faidx.set_coordinate_offset("chr1", -100);
+    /// let seq = faidx.query("chr1", 100, 110).unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    ///
+    /// // Shifting below zero is an error
+    /// assert!(faidx.query("chr1", 50, 110).is_err());
+    /// ```
+    pub fn set_coordinate_offset(&mut self, name: &str, delta: isize) {
+        let name = self.canonical_name(name).to_string();
+        if delta == 0 {
+            self.offsets.remove(&name);
+        } else {
+            self.offsets.insert(name, delta);
+        }
+    }
+
+    /// Register alternative names for contigs, replacing any previous aliases.
+    ///
+    /// Each `(alias, name)` pair lets `alias` be used in place of the contig `name`
+    /// wherever a contig name is accepted, e.g. to query `chr1` as `1` or
+    /// `NC_000001.11`. An exact contig name always takes precedence over an alias, so
+    /// an alias that is also the name of a contig refers to that contig.
+    /// Aliases are resolved once: an alias pointing to another alias or to a missing
+    /// contig behaves like an unknown name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    /// use std::collections::HashMap;
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let aliases = HashMap::from([("1".to_string(), "chr1".to_string())]);
+    /// faidx.set_aliases(aliases);
+    /// assert_eq!(faidx.query("1", 0, 10).unwrap(), b"ACCTACGATC");
+    /// assert_eq!(faidx.seq_len("1"), Some(112));
+    /// ```
+    pub fn set_aliases<I: IntoIterator<Item = (String, String)>>(&mut self, aliases: I) {
+        self.aliases = aliases.into_iter().collect();
+        // The cached entry is keyed by the queried name, which may now resolve differently
+        self.last = None;
+    }
+
+    /// Attach a set of feature intervals, replacing any previous features.
+    ///
+    /// The features are looked up with `overlapping_features`.
+    pub fn set_features(&mut self, features: IntervalStore) {
+        self.features = features;
+    }
+
+    /// Returns the features attached with `set_features` that contain position `pos`.
+    ///
+    /// Each feature is returned as `(start, end, id)`, sorted by start. Features are
+    /// stored under contig names, so `name` may also be an alias of a contig.
+    /// Positions are compared to the feature intervals as given, without applying
+    /// coordinate offsets.
+    ///
+    /// # Errors
+    ///
+    /// Error if the query `name` is not found in the index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta, IntervalStore};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let mut features = IntervalStore::new();
+    /// features.insert("chr1", 0, 10, "promoter");
+    /// features.insert("chr1", 5, 50, "gene");
+    /// faidx.set_features(features);
+    ///
+    /// let hits = faidx.overlapping_features("chr1", 7).unwrap();
+    /// assert_eq!(hits, vec![(0, 10, "promoter"), (5, 50, "gene")]);
+    ///
+    /// // sequence and annotation together
+    /// let (start, end, _) = hits[1];
+    /// assert_eq!(faidx.query("chr1", start, end).unwrap().len(), 45);
+    /// ```
+    pub fn overlapping_features(
+        &self,
+        name: &str,
+        pos: usize,
+    ) -> Result<Vec<(usize, usize, &str)>> {
+        if self.entry(name).is_none() {
+            bail!("No entry found for {}", name);
+        }
+        Ok(self.features.overlapping(self.canonical_name(name), pos))
+    }
+
+    /// Returns the contig name that `name` refers to, resolving aliases.
+    ///
+    /// Names that are neither a contig nor an alias are returned unchanged.
+    fn canonical_name<'a>(&'a self, name: &'a str) -> &'a str {
+        if self.index.get(name).is_some() {
+            return name;
+        }
+        match self.aliases.get(name) {
+            Some(canonical) => canonical,
+            None => name,
+        }
+    }
+
+    /// Returns the index entry of a contig by name or alias.
+    fn entry(&self, name: &str) -> Option<&IndexEntry> {
+        self.index.get(self.canonical_name(name))
+    }
+
+    /// Check whether a region can be queried.
+    ///
+    /// This combines the existence and bounds checks of `query` into a single value
+    /// which carries the contig length, so callers can report the valid range without
+    /// parsing error messages. Any coordinate offset registered with
+    /// `set_coordinate_offset` is applied as in `query`, and a region that the offset
+    /// shifts below zero is `RegionCheck::OutOfBounds`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta, RegionCheck};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// assert_eq!(faidx.check_region("chr1", 0, 10), RegionCheck::Valid { length: 112 });
+    ///
+    /// match faidx.check_region("chr1", 100, 500) {
+    ///     RegionCheck::OutOfBounds { length } => {
+    ///         assert_eq!(
+    ///             format!("requested 100-500 but chr1 is only {} bp", length),
+    ///             "requested 100-500 but chr1 is only 112 bp"
+    ///         );
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn check_region(&self, name: &str, start: usize, end: usize) -> RegionCheck {
+        let length = match self.entry(name) {
+            Some(entry) => entry.length,
+            None => return RegionCheck::MissingContig,
+        };
+        let (start, end) = match self.shift_interval(name, start, end) {
+            Ok(interval) => interval,
+            Err(_) => return RegionCheck::OutOfBounds { length },
+        };
+        if start >= end {
+            RegionCheck::EmptyInterval { length }
+        } else if end > length {
+            RegionCheck::OutOfBounds { length }
+        } else {
+            RegionCheck::Valid { length }
+        }
+    }
+
+    /// Returns `true` if `query` would accept the region.
+    ///
+    /// This runs the same lookup and bounds checks as `query`, including any coordinate
+    /// offset registered with `set_coordinate_offset`, without reading any sequence.
+    /// It is meant for cheaply filtering candidate regions before querying them; use
+    /// `check_region` to find out why a region is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let candidates = [("chr1", 0, 10), ("chr1", 100, 500), ("chr3", 0, 10), ("chr2", 5, 5)];
+    /// let valid = candidates
+    ///     .into_iter()
+    ///     .filter(|&(name, start, end)| faidx.is_valid_region(name, start, end))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(valid, vec![("chr1", 0, 10)]);
+    /// ```
+    pub fn is_valid_region(&self, name: &str, start: usize, end: usize) -> bool {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => return false,
+        };
+        match self.shift_interval(name, start, end) {
+            Ok((start, end)) => validate_interval(entry, start, end, true).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// Reopen the FASTA file from `path`, keeping the index and settings.
+    ///
+    /// The new file replaces the memory map (the open file of `Backend::File`, or the
+    /// bytes of `Backend::InMemory`, which are read from `path`) so a
+    /// reference replaced on disk can be swapped in without rebuilding the index, and
+    /// the previous map is released. Contigs loaded with `load_contig` are unloaded.
+    ///
+    /// As a sanity check the new file must be long enough to hold the sequence of every
+    /// indexed contig. Call `validate` for a complete check of the new file.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the file cannot be opened or memory-mapped.
+    /// - Error if the file is shorter than the end of the last indexed sequence.
+    /// - Error if the FASTA file is BGZF-compressed, since its `.gzi` index would also
+    ///   need to be replaced.
+    ///
+    /// On error the `IndexedFasta` keeps reading the previous file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// faidx.remap("example_data/example.fa").unwrap();
+    /// assert_eq!(faidx.query("chr1", 0, 10).unwrap(), b"ACCTACGATC");
+    ///
+    /// // masked.fa is too short for the index of example.fa
+    /// assert!(faidx.remap("example_data/masked.fa").is_err());
+    /// ```
+    pub fn remap(&mut self, path: &str) -> Result<()> {
+        let file = File::open(path)?;
+        let storage = match &self.storage {
+            Storage::Mmap(_) => Storage::Mmap(unsafe { Mmap::map(&file)? }),
+            Storage::File(_) => Storage::File(Mutex::new(file)),
+            Storage::InMemory(_) => Storage::InMemory(std::fs::read(path)?),
+            Storage::Bgzf { .. } => {
+                bail!("Remapping is not supported on BGZF-compressed FASTA files")
+            }
+        };
+        let required = self
+            .index
+            .get_entries()
+            .values()
+            .filter(|entry| entry.length > 0)
+            .map(|entry| {
+                let last = entry.length - 1;
+                entry.offset
+                    + (last / entry.line_bases) * entry.line_width
+                    + last % entry.line_bases
+                    + 1
+            })
+            .max()
+            .unwrap_or(0);
+        let file_len = storage.len()?;
+        if file_len < required {
+            bail!(
+                "{} is {} bytes but the index requires at least {} bytes",
+                path,
+                file_len,
+                required
+            );
+        }
+        self.storage = storage;
+        self.loaded.clear();
+        if let Some(cache) = &mut self.cache {
+            cache.clear();
+        }
+        Ok(())
+    }
+
+    /// Validate the index against the FASTA file.
+    ///
+    /// A stale or mismatched index silently produces garbage sequence, so this can be
+    /// called right after `new` to fail fast. For every entry (in file order) it checks:
+    ///
+    /// - The `offset` points just after a `>name` header line.
+    /// - The first sequence line is terminated after `line_bases` bases.
+    /// - Reading `length` bases at `line_width` spacing stays within the file.
+    /// - The sequence is followed only by line terminators up to the next header, and the
+    ///   last indexed sequence is followed by the end of the file.
+    ///
+    /// An empty index only matches a FASTA file that is empty or holds only whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Error on the first inconsistency, naming the contig and the reason.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    /// assert!(faidx.validate().is_ok());
+    ///
+    /// // The index of another file does not match
+    /// let index = FastaIndex::from_filepath("example_data/masked.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    /// assert!(faidx.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let file_len = self.storage.len()?;
+        let entries = self.ordered_entries();
+        if entries.is_empty() {
+            let mut pos = 0;
+            while pos < file_len {
+                let window = self.storage.fetch(pos, (file_len - pos).min(64))?;
+                if window.iter().any(|c| !c.is_ascii_whitespace()) {
+                    bail!("Index is empty but the FASTA file is not");
+                }
+                pos += window.len();
+            }
+        }
+        for (idx, entry) in entries.iter().enumerate() {
+            let is_last = idx + 1 == entries.len();
+            self.validate_entry(entry, file_len, is_last)
+                .map_err(|e| anyhow!("Invalid index entry for {}: {}", entry.name, e))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the line-ending style used by a contig.
+    ///
+    /// This is inferred from the difference between the `line_width` and `line_bases`
+    /// of the contig's `IndexEntry`:
+    ///
+    /// - `1` is a `LineEnding::Lf` (`\n`)
+    /// - `2` is a `LineEnding::CrLf` (`\r\n`)
+    /// - `0` is a `LineEnding::None` (the sequence is not wrapped)
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the line terminator width is not one of the above.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta, LineEnding};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// assert_eq!(faidx.line_ending("chr1").unwrap(), LineEnding::Lf);
+    /// ```
+    pub fn line_ending(&self, name: &str) -> Result<LineEnding> {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        match entry.line_width.checked_sub(entry.line_bases) {
+            Some(0) => Ok(LineEnding::None),
+            Some(1) => Ok(LineEnding::Lf),
+            Some(2) => Ok(LineEnding::CrLf),
+            _ => bail!(
+                "Unrecognized line ending for {} (line width {} and line bases {})",
+                name,
+                entry.line_width,
+                entry.line_bases
+            ),
+        }
+    }
+
+    /// Apply the coordinate offset registered for a contig (if any) to a query interval.
+    fn shift_interval(&self, name: &str, start: usize, end: usize) -> Result<(usize, usize)> {
+        if self.offsets.is_empty() {
+            return Ok((start, end));
+        }
+        let delta = match self.offsets.get(self.canonical_name(name)) {
+            Some(&delta) => delta,
+            None => return Ok((start, end)),
+        };
+        let shift = |pos: usize| match pos.checked_add_signed(delta) {
+            Some(pos) => Ok(pos),
+            None => bail!(
+                "Coordinate offset {} shifts position {} of {} below zero",
+                delta,
+                pos,
+                name
+            ),
+        };
+        Ok((shift(start)?, shift(end)?))
+    }
+
+    /// Copy an interval into the internal buffer and remove all newline characters.
+    ///
+    /// The coordinates are used as given (no coordinate offset is applied).
+    /// If the interval is not `bounded` the `end` position is truncated to the sequence length.
+    pub(crate) fn fill_buffer(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+        bounded: bool,
+    ) -> Result<&[u8]> {
+        self.buffer.clear();
+        self.append_buffer(name, start, end, bounded)?;
+        Ok(&self.buffer)
+    }
+
+    /// Append an interval to the internal buffer without its newline characters.
+    ///
+    /// The coordinates are used as given (no coordinate offset is applied).
+    /// If the interval is not `bounded` the `end` position is truncated to the sequence length.
+    fn append_buffer(&mut self, name: &str, start: usize, end: usize, bounded: bool) -> Result<()> {
+        // Repeated queries by the same name reuse the geometry of the last entry without
+        // resolving aliases or hashing into the index
+        let geometry = match &mut self.last {
+            Some((cached, geometry)) if cached == name => *geometry,
+            _ => {
+                let geometry = match self.entry(name) {
+                    Some(entry) => LineGeometry::from(entry),
+                    None => bail!("No entry found for {}", name),
+                };
+                match &mut self.last {
+                    // Reuse the allocation of the cached name
+                    Some((cached, last)) => {
+                        cached.clear();
+                        cached.push_str(name);
+                        *last = geometry;
+                    }
+                    None => self.last = Some((name.to_string(), geometry)),
+                }
+                geometry
+            }
+        };
+        validate_bounds(geometry.length, start, end, bounded)?;
+        let end = end.min(geometry.length);
+        if !self.loaded.is_empty() {
+            if let Some(seq) = self.loaded.get(self.canonical_name(name)) {
+                self.buffer.extend_from_slice(&seq[start..end]);
+                return Ok(());
+            }
+        }
+        let query_pos = QueryPosition::from_geometry(start, end, geometry);
+        let offset = self.buffer.len();
+        self.storage
+            .extend_bases(&query_pos, &mut self.buffer)
+            .map_err(|e| read_error(name, e))?;
+        if self.uppercase {
+            self.buffer[offset..].make_ascii_uppercase();
+        }
+        Ok(())
+    }
+
+    /// Return the raw slice of an interval (including newline characters) without copying.
+    ///
+    /// The coordinates are used as given (no coordinate offset is applied).
+    /// If the interval is not `bounded` the `end` position is truncated to the sequence length.
+    fn slice_interval(&self, name: &str, start: usize, end: usize, bounded: bool) -> Result<&[u8]> {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        validate_interval(entry, start, end, bounded)?;
+        let end = end.min(entry.length);
+        let query_pos = QueryPosition::new(start, end, entry);
+        self.storage
+            .slice_query(&query_pos)
+            .map_err(|e| read_error(name, e))
+    }
+
+    /// Return the raw bytes of an interval (including newline characters).
+    ///
+    /// The coordinate offset of the contig is applied and the interval is validated.
+    /// This only copies the sequence when the storage is compressed.
+    fn fetch_interval(&self, name: &str, start: usize, end: usize) -> Result<Cow<'_, [u8]>> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        validate_interval(entry, start, end, true)?;
+        let query_pos = QueryPosition::new(start, end, entry);
+        self.storage
+            .fetch_query(&query_pos)
+            .map_err(|e| read_error(name, e))
+    }
+
+    /// Check a single entry against the FASTA file (see `validate`).
+    fn validate_entry(&self, entry: &IndexEntry, file_len: usize, is_last: bool) -> Result<()> {
+        if entry.offset == 0 || entry.offset > file_len {
+            bail!("offset {} does not follow a header line", entry.offset);
+        }
+        let range = self.header_range(entry.offset)?;
+        let header = self.storage.fetch(range.start, range.len())?;
+        let name = header
+            .strip_prefix(b">")
+            .and_then(|header| header.split(|c| c.is_ascii_whitespace()).next());
+        if name != Some(entry.name.as_bytes()) {
+            bail!(
+                "offset {} does not follow a >{} header line",
+                entry.offset,
+                entry.name
+            );
+        }
+        if entry.length == 0 {
+            return Ok(());
+        }
+        if entry.line_bases == 0 || entry.line_width < entry.line_bases {
+            bail!(
+                "invalid line geometry ({} bases, {} bytes per line)",
+                entry.line_bases,
+                entry.line_width
+            );
+        }
+
+        // The first line must end after `line_bases` bases
+        if entry.length > entry.line_bases {
+            let terminator = self.storage.fetch(entry.offset + entry.line_bases, 1)?;
+            if !matches!(terminator[0], b'\n' | b'\r') {
+                bail!("first line is longer than {} bases", entry.line_bases);
+            }
+        }
+
+        let full_lines = entry.length / entry.line_bases;
+        let remainder = entry.length % entry.line_bases;
+        let seq_end = if remainder > 0 {
+            entry.offset + full_lines * entry.line_width + remainder
+        } else {
+            entry.offset + (full_lines - 1) * entry.line_width + entry.line_bases
+        };
+        if seq_end > file_len {
+            bail!(
+                "sequence ends at byte {} but the file is only {} bytes",
+                seq_end,
+                file_len
+            );
+        }
+
+        // Skip the line terminators following the sequence
+        let mut pos = seq_end;
+        while pos < file_len {
+            let window = self.storage.fetch(pos, (file_len - pos).min(64))?;
+            match window.iter().position(|&c| !matches!(c, b'\n' | b'\r')) {
+                Some(skip) => {
+                    pos += skip;
+                    break;
+                }
+                None => pos += window.len(),
+            }
+        }
+        if pos == file_len {
+            if !is_last {
+                bail!("sequence runs into the end of the file before the next contig");
+            }
+        } else if self.storage.fetch(pos, 1)?[0] != b'>' {
+            bail!("sequence is longer than {} bases", entry.length);
+        } else if is_last {
+            bail!("the file contains records after the last indexed contig");
+        }
+        Ok(())
+    }
+
+    /// Returns the byte range of the line ending just before `offset`, without its line
+    /// terminator.
+    ///
+    /// The sequence of an entry starts on the line after its header, so the header line
+    /// ends with the `\n` at `offset - 1` (preceded by `\r` for CRLF files) and starts
+    /// after the previous `\n`, or at the start of the file for the first record.
+    /// That newline is found by scanning backwards from `offset` in growing windows.
+    fn header_range(&self, offset: usize) -> Result<Range<usize>> {
+        if offset == 0 || self.storage.fetch(offset - 1, 1)?[0] != b'\n' {
+            bail!("offset {} does not follow a header line", offset);
+        }
+        let mut lookback = 256;
+        loop {
+            let start = offset.saturating_sub(lookback);
+            let bytes = self.storage.fetch(start, offset - 1 - start)?;
+            let line_start = match bytes.iter().rposition(|&c| c == b'\n') {
+                Some(pos) => start + pos + 1,
+                None if start == 0 => 0,
+                None => {
+                    lookback *= 2;
+                    continue;
+                }
+            };
+            let line_end = match bytes.last() {
+                Some(b'\r') => offset - 2,
+                _ => offset - 1,
+            };
+            return Ok(line_start..line_end.max(line_start));
+        }
+    }
+
+    /// Returns all entries in the order they appear in the FASTA file.
+    fn ordered_entries(&self) -> Vec<&IndexEntry> {
+        let mut entries = self.index.get_entries().values().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.offset);
+        entries
+    }
+
+    /// Query the FASTA file by name and position.
+    ///
+    /// The sequence is returned as a `&[u8]` slice but is not guaranteed to be valid UTF-8.
+    /// It also removes all line terminators (`\n` or `\r\n`) from the sequence slice.
+    ///
+    /// To query intervals that are potentially out of bounds, use `query_unbounded` and
+    /// return a truncated sequence use `query_unbounded`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name`is not found in the index.
+    /// - Error if the `start` position is greater than the `end` position.
+    /// - Error if the `start` position is equal to the `end` position.
+    /// - Error if the `end` position is greater than the index sequence length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Query a region from the middle of chr1
+    /// let seq = faidx.query("chr1", 50, 80).unwrap();
+    /// assert_eq!(seq.len(), 30);
+    ///
+    /// // Overextend the query into chr1 (which is 112 bases long)
+    /// let seq = faidx.query("chr1", 100, 120);
+    /// assert!(seq.is_err());
+    /// ```
+    pub fn query(&mut self, name: &str, start: usize, end: usize) -> Result<Sequence<'_>> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        if self.cache.is_some() {
+            self.cache_contig(name)?;
+        }
+        if !self.loaded.is_empty() && self.loaded.contains_key(self.canonical_name(name)) {
+            return self.slice_loaded(name, start, end).map(Sequence::new);
+        }
+        self.fill_buffer(name, start, end, true).map(Sequence::new)
+    }
+
+    /// Query the FASTA file by name and position with `u64` coordinates.
+    ///
+    /// On 32-bit targets (including `wasm32`) `usize` cannot hold the positions of
+    /// references larger than 4 GB. This computes the byte offset of the end of the
+    /// interval in the file with `u64` arithmetic and checks that it, and the positions,
+    /// fit in a `usize` before delegating to `query`, so an interval beyond the
+    /// addressable range is an error rather than a silently wrapped offset.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if `start`, `end`, or the file offset of the interval does not fit in a
+    ///   `usize` on the current platform.
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx.query_u64("chr1", 0, 10).unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    /// assert!(faidx.query_u64("chr1", 0, u64::MAX).is_err());
+    /// ```
+    pub fn query_u64(&mut self, name: &str, start: u64, end: u64) -> Result<Sequence<'_>> {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        let to_usize = |value: u64, label: &str| match usize::try_from(value) {
+            Ok(value) => Ok(value),
+            Err(_) => bail!(
+                "{} {} of {} does not fit in usize on this platform",
+                label,
+                value,
+                name
+            ),
+        };
+        if let (Some(line_bases), Some(line_width)) = (
+            std::num::NonZeroU64::new(entry.line_bases as u64),
+            std::num::NonZeroU64::new(entry.line_width as u64),
+        ) {
+            let end_offset = (end / line_bases)
+                .checked_mul(line_width.get())
+                .and_then(|rows| rows.checked_add(end % line_bases))
+                .and_then(|pos| pos.checked_add(entry.offset as u64));
+            match end_offset {
+                Some(end_offset) => to_usize(end_offset, "File offset")?,
+                None => bail!("File offset of position {} of {} overflows u64", end, name),
+            };
+        }
+        let (start, end) = (
+            to_usize(start, "Start position")?,
+            to_usize(end, "End position")?,
+        );
+        self.query(name, start, end)
+    }
+
+    /// Query the FASTA file by name and position and return the contig's `IndexEntry`.
+    ///
+    /// This behaves exactly like `query` but also returns a copy of the index entry of
+    /// the contig (its length and line geometry), saving a second lookup with
+    /// `FastaIndex::get`. The entry is cloned since the sequence borrows the internal
+    /// buffer.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let (entry, seq) = faidx.query_with_entry("chr2", 0, 4).unwrap();
+    /// assert_eq!(entry.length, 176);
+    /// assert_eq!(seq, b"TTTT");
+    /// ```
+    pub fn query_with_entry(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(IndexEntry, Sequence<'_>)> {
+        let entry = match self.entry(name) {
+            Some(entry) => entry.clone(),
+            None => bail!("No entry found for {}", name),
+        };
+        let seq = self.query(name, start, end)?;
+        Ok((entry, seq))
+    }
+
+    /// Decode the full sequence of a contig once and keep it in memory.
+    ///
+    /// The contig is stored without newlines (and uppercased if the `IndexedFasta` was
+    /// built with `uppercase`), after which `query` slices it directly instead of
+    /// copying and stripping the bytes of every query, and the other buffered query
+    /// methods copy from it. This trades the memory of the whole contig for speed when
+    /// the same contig is queried many times. Loading an already loaded contig does
+    /// nothing. Use `unload_contig` to free it.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the sequence cannot be read from the FASTA file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// faidx.load_contig("chr1").unwrap();
+    /// assert!(faidx.is_loaded("chr1"));
+    /// assert_eq!(faidx.query("chr1", 0, 10).unwrap(), b"ACCTACGATC");
+    ///
+    /// assert!(faidx.unload_contig("chr1"));
+    /// assert!(!faidx.is_loaded("chr1"));
+    /// ```
+    pub fn load_contig(&mut self, name: &str) -> Result<()> {
+        let name = self.canonical_name(name).to_string();
+        if self.loaded.contains_key(&name) {
+            return Ok(());
+        }
+        let seq = self.query_all(&name)?.to_vec();
+        if let Some(cache) = &mut self.cache {
+            for evicted in cache.insert(&name, seq.len()) {
+                self.loaded.remove(&evicted);
+            }
+        }
+        self.loaded.insert(name, seq);
+        Ok(())
+    }
+
+    /// Load a contig into the cache of `with_cache` on a miss, or promote it on a hit.
+    fn cache_contig(&mut self, name: &str) -> Result<()> {
+        let length = match self.entry(name) {
+            Some(entry) => entry.length,
+            None => bail!("No entry found for {}", name),
+        };
+        let name = self.canonical_name(name).to_string();
+        if self.loaded.contains_key(&name) {
+            if let Some(cache) = &mut self.cache {
+                cache.touch(&name);
+            }
+        } else if self.cache.as_ref().is_some_and(|cache| cache.fits(length)) {
+            self.load_contig(&name)?;
+        }
+        Ok(())
+    }
+
+    /// Free the in-memory sequence of a contig loaded with `load_contig`.
+    ///
+    /// Returns `true` if the contig was loaded.
+    pub fn unload_contig(&mut self, name: &str) -> bool {
+        let name = self.canonical_name(name).to_string();
+        if let Some(cache) = &mut self.cache {
+            cache.remove(&name);
+        }
+        self.loaded.remove(&name).is_some()
+    }
+
+    /// Returns `true` if the contig has been loaded with `load_contig`.
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.loaded.contains_key(self.canonical_name(name))
+    }
+
+    /// Query an interval into the internal buffer like `query`, even if the contig is loaded.
+    ///
+    /// Use this instead of `query` to modify the sequence in the buffer afterwards.
+    fn query_buffered(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        self.fill_buffer(name, start, end, true)
+    }
+
+    /// Slice a validated interval from a contig loaded with `load_contig`.
+    fn slice_loaded(&self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        let (entry, seq) = match (self.entry(name), self.loaded.get(self.canonical_name(name))) {
+            (Some(entry), Some(seq)) => (entry, seq),
+            _ => bail!("No loaded contig found for {}", name),
+        };
+        validate_interval(entry, start, end, true)?;
+        Ok(&seq[start..end])
+    }
+
+    /// Query the FASTA file by the 0-based position of a contig in the index.
+    ///
+    /// Contigs are ordered as in the `.fai` file (see `FastaIndex::get_nth`), which makes
+    /// this convenient for iterating over a whole genome without handling names.
+    /// Otherwise this behaves exactly like `query`.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// - Error if `i` is not less than the number of contigs in the index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr2 is the second contig of the index
+    /// let seq = faidx.query_nth(1, 0, 10).unwrap();
+    /// assert_eq!(seq, b"TTTTGATCGA");
+    ///
+    /// assert!(faidx.query_nth(2, 0, 10).is_err());
+    /// ```
+    pub fn query_nth(&mut self, i: usize, start: usize, end: usize) -> Result<Sequence<'_>> {
+        let name = match self.index.get_nth(i) {
+            Some(entry) => entry.name.clone(),
+            None => bail!("No entry found at index position {}", i),
+        };
+        self.query(&name, start, end)
+    }
+
+    /// Query the FASTA file by name and position and return the sequence as a `&str`.
+    ///
+    /// This behaves exactly like `query` but validates that the sequence is UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// - Error if the sequence is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx.query_str("chr1", 0, 10).unwrap();
+    /// assert_eq!(seq, "ACCTACGATC");
+    /// ```
+    pub fn query_str(&mut self, name: &str, start: usize, end: usize) -> Result<&str> {
+        let seq = self.query(name, start, end)?;
+        match std::str::from_utf8(seq.as_bytes()) {
+            Ok(seq) => Ok(seq),
+            Err(err) => bail!(
+                "Sequence of {}:{}-{} is not valid UTF-8: {}",
+                name,
+                start,
+                end,
+                err
+            ),
+        }
+    }
+
+    /// Query the FASTA file and borrow the sequence when no newlines need to be removed.
+    ///
+    /// This unifies `query` and `query_buffer`: when the interval lies within a single
+    /// line the sequence is returned as `Cow::Borrowed` directly from the memory map, and
+    /// otherwise it is returned as `Cow::Owned` with all newline characters removed.
+    /// Either way the result is the same sequence `query` would return, and this only
+    /// requires `&self`.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    /// use std::borrow::Cow;
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Within the first line of chr1 (28 bases) the sequence is borrowed
+    /// let seq = faidx.query_cow("chr1", 0, 10).unwrap();
+    /// assert!(matches!(seq, Cow::Borrowed(_)));
+    /// assert_eq!(&*seq, b"ACCTACGATC");
+    ///
+    /// // Spanning a newline the sequence is copied without the newline
+    /// let seq = faidx.query_cow("chr1", 20, 40).unwrap();
+    /// assert!(matches!(seq, Cow::Owned(_)));
+    /// assert_eq!(seq.len(), 20);
+    /// ```
+    pub fn query_cow(&self, name: &str, start: usize, end: usize) -> Result<Cow<'_, [u8]>> {
+        match self.fetch_interval(name, start, end)? {
+            // An interval ending at a line end also spans the line's terminator
+            Cow::Borrowed(bytes) => {
+                let seq_end = bytes
+                    .iter()
+                    .rposition(|&c| !is_line_break(c))
+                    .map_or(0, |pos| pos + 1);
+                let bytes = &bytes[..seq_end];
+                if bytes.iter().any(|&c| is_line_break(c)) {
+                    Ok(Cow::Owned(self.normalize(bytes)))
+                } else if self.uppercase && bytes.iter().any(u8::is_ascii_lowercase) {
+                    Ok(Cow::Owned(bytes.to_ascii_uppercase()))
+                } else {
+                    Ok(Cow::Borrowed(bytes))
+                }
+            }
+            Cow::Owned(bytes) => Ok(Cow::Owned(self.normalize(&bytes))),
+        }
+    }
+
+    /// Copy raw bytes without their newline characters, uppercasing them if configured.
+    fn normalize(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut seq = strip_newlines(bytes);
+        if self.uppercase {
+            seq.make_ascii_uppercase();
+        }
+        seq
+    }
+
+    /// Query the FASTA file from a position to the end of the contig.
+    ///
+    /// This resolves the contig length and queries `start..length`, removing all
+    /// newline characters from the sequence as in `query`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the `start` position is not less than the sequence length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr2 is 176bp
+    /// let seq = faidx.query_from("chr2", 170).unwrap();
+    /// assert_eq!(seq, b"ACCACA");
+    ///
+    /// assert!(faidx.query_from("chr2", 176).is_err());
+    /// ```
+    pub fn query_from(&mut self, name: &str, start: usize) -> Result<Sequence<'_>> {
+        let (start, _) = self.shift_interval(name, start, start)?;
+        let length = match self.entry(name) {
+            Some(entry) => entry.length,
+            None => bail!("No entry found for {}", name),
+        };
+        if start >= length {
+            bail!("Start position must be less than sequence length");
+        }
+        self.fill_buffer(name, start, length, true)
+            .map(Sequence::new)
+    }
+
+    /// Query the FASTA file by name and a range of positions.
+    ///
+    /// The range is 0-based like `query`, so `10..40` is equivalent to
+    /// `query(name, 10, 40)` and `10..=40` includes position `40`. An unbounded start is
+    /// the start of the contig and an unbounded end is the end of the contig, so `..`
+    /// queries the whole contig.
+    /// Newline characters are removed from the sequence as in `query`.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// assert_eq!(faidx.query_range("chr1", 0..10).unwrap(), b"ACCTACGATC");
+    /// assert_eq!(faidx.query_range("chr1", ..=9).unwrap(), b"ACCTACGATC");
+    /// assert_eq!(faidx.query_range("chr2", 170..).unwrap(), b"ACCACA");
+    /// assert_eq!(faidx.query_range("chr2", ..).unwrap().len(), 176);
+    /// assert!(faidx.query_range("chr2", 170..180).is_err());
+    /// ```
+    pub fn query_range<R: RangeBounds<usize>>(
+        &mut self,
+        name: &str,
+        range: R,
+    ) -> Result<Sequence<'_>> {
+        let length = match self.entry(name) {
+            Some(entry) => entry.length,
+            None => bail!("No entry found for {}", name),
+        };
+        let shift = |pos: usize| self.shift_interval(name, pos, pos).map(|(pos, _)| pos);
+        let start = match range.start_bound() {
+            Bound::Included(&start) => shift(start)?,
+            Bound::Excluded(&start) => shift(start.saturating_add(1))?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => shift(end.saturating_add(1))?,
+            Bound::Excluded(&end) => shift(end)?,
+            Bound::Unbounded => length,
+        };
+        self.fill_buffer(name, start, end, true).map(Sequence::new)
+    }
+
+    /// Query the FASTA file by name and position into a caller-provided buffer.
+    ///
+    /// This behaves exactly like `query` (the buffer `out` is cleared and receives the
+    /// sequence without newline characters) but takes `&self` so a single `IndexedFasta`
+    /// can be shared across threads, each with its own buffer.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let mut out = Vec::new();
+    /// faidx.query_into("chr1", 0, 10, &mut out).unwrap();
+    /// assert_eq!(out, b"ACCTACGATC");
+    /// ```
+    pub fn query_into(
+        &self,
+        name: &str,
+        start: usize,
+        end: usize,
+        out: &mut Vec<u8>,
+    ) -> Result<()> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        validate_interval(entry, start, end, true)?;
+        out.clear();
+        let query_pos = QueryPosition::new(start, end, entry);
+        self.storage
+            .extend_bases(&query_pos, out)
+            .map_err(|e| read_error(name, e))?;
+        if self.uppercase {
+            out.make_ascii_uppercase();
+        }
+        Ok(())
+    }
+
+    /// Query the FASTA file and report the interval in both contig and genome coordinates.
+    ///
+    /// Returns the newline-stripped sequence (as `query`) along with
+    /// `(contig_start, contig_end, genome_start, genome_end)`. Genome coordinates place
+    /// the interval on the concatenation of all contigs in file order, i.e. they are
+    /// offset by the total length of all contigs preceding `name`.
+    ///
+    /// If a coordinate offset is registered for the contig the reported contig
+    /// coordinates are the shifted coordinates that were read from the FASTA.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr1 is 112bp so chr2 starts at genome position 112
+    /// let (seq, start, end, genome_start, genome_end) =
+    ///     faidx.query_dual_coords("chr2", 0, 10).unwrap();
+    /// assert_eq!(seq, b"TTTTGATCGA");
+    /// assert_eq!((start, end), (0, 10));
+    /// assert_eq!((genome_start, genome_end), (112, 122));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn query_dual_coords(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(Vec<u8>, usize, usize, usize, usize)> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        let seq = self.fill_buffer(name, start, end, true)?.to_vec();
+        let canonical = self.canonical_name(name);
+        let genome_offset = self
+            .ordered_entries()
+            .into_iter()
+            .take_while(|entry| entry.name != canonical)
+            .map(|entry| entry.length)
+            .sum::<usize>();
+        Ok((seq, start, end, genome_offset + start, genome_offset + end))
+    }
+
+    /// Query the FASTA file by name and 1-based inclusive position.
+    ///
+    /// This accepts coordinates as used by GFF, VCF, and SAM, where the first base of a
+    /// contig is `1` and `end` is included in the result. The interval is converted to
+    /// the 0-based half-open interval `start - 1..end` and dispatched to `query`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the `start` position is `0`.
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr1:1-10 is the same as the 0-based interval 0..10
+    /// let seq = faidx.query_1based("chr1", 1, 10).unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    ///
+    /// // A single base
+    /// let seq = faidx.query_1based("chr1", 4, 4).unwrap();
+    /// assert_eq!(seq, b"T");
+    ///
+    /// // There is no position 0 in 1-based coordinates
+    /// assert!(faidx.query_1based("chr1", 0, 10).is_err());
+    /// ```
+    pub fn query_1based(&mut self, name: &str, start: usize, end: usize) -> Result<Sequence<'_>> {
+        self.query_with_mode(name, start, end, IntervalMode::Closed1)
+    }
+
+    /// Query the FASTA file with an interval in an explicit coordinate convention.
+    ///
+    /// The interval is normalized to a 0-based half-open interval according to `mode`
+    /// and dispatched to `query`. The other query methods all take `IntervalMode::HalfOpen0`
+    /// intervals, and `query_1based` is a shortcut for `IntervalMode::Closed1`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the `start` position of an `IntervalMode::Closed1` interval is `0`.
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta, IntervalMode};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx
+    ///     .query_with_mode("chr1", 0, 10, IntervalMode::HalfOpen0)
+    ///     .unwrap()
+    ///     .to_vec();
+    /// assert_eq!(
+    ///     faidx.query_with_mode("chr1", 1, 10, IntervalMode::Closed1).unwrap(),
+    ///     seq
+    /// );
+    /// ```
+    pub fn query_with_mode(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+        mode: IntervalMode,
+    ) -> Result<Sequence<'_>> {
+        let (start, end) = match mode.to_half_open(start, end) {
+            Ok(interval) => interval,
+            Err(e) => bail!("{} for {}", e, name),
+        };
+        self.query(name, start, end)
+    }
+
+    /// Query the FASTA file by name and position but do not copy to internal buffer.
+    ///
+    /// This will **not** remove newline characters from the sequence slice.
+    ///
+    /// This method is useful for memory-efficient operations where the sequence is not
+    /// necessarily needed as a `&str` slice (since newlines are potentially included).
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the `start` position is greater than the `end` position.
+    /// - Error if the `start` position is equal to the `end` position.
+    /// - Error if the `end` position is greater than the index sequence length.
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Query a region from the middle of chr1 (note the newline character is included)
+    /// let seq = faidx.query_buffer("chr1", 50, 80).unwrap();
+    /// assert_eq!(seq.len(), 31);
+    /// assert!(seq.contains(&b'\n'));
+    /// assert_eq!(seq.iter().filter(|&&c| c != b'\n').count(), 30);
+    ///
+    /// // Overextend the query into chr1 (which is 112 bases long)
+    /// let seq = faidx.query_buffer("chr1", 100, 120);
+    /// assert!(seq.is_err());
+    /// ```
+    pub fn query_buffer(&self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        self.slice_interval(name, start, end, true)
+    }
+
+    /// Query the FASTA file and return the positions of the line breaks in the region.
+    ///
+    /// The sequence is returned as by `query` together with the indices into it at
+    /// which a line break occurred in the file, so the original wrapping can be
+    /// restored after editing the sequence instead of being inferred from `line_bases`.
+    /// A line break at index `i` follows the first `i` bases of the sequence; an index
+    /// equal to the sequence length means the region ends at the end of a line.
+    /// `\r\n` terminators are reported once, like `\n`.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr1 is wrapped at 28 bases
+    /// let (seq, newlines) = faidx.query_with_newlines("chr1", 20, 60).unwrap();
+    /// assert_eq!(seq.len(), 40);
+    /// assert_eq!(newlines, vec![8, 36]);
+    /// ```
+    pub fn query_with_newlines(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(&[u8], Vec<usize>)> {
+        // Strip the raw bytes into the buffer and record the line breaks in one pass
+        let mut seq = std::mem::take(&mut self.buffer);
+        seq.clear();
+        let newlines = self.fetch_interval(name, start, end).map(|raw| {
+            let mut newlines = Vec::new();
+            for &c in raw.iter() {
+                match c {
+                    b'\n' => newlines.push(seq.len()),
+                    b'\r' => {}
+                    _ => seq.push(c),
+                }
+            }
+            newlines
+        });
+        if self.uppercase {
+            seq.make_ascii_uppercase();
+        }
+        self.buffer = seq;
+        Ok((&self.buffer, newlines?))
+    }
+
+    /// Query the FASTA file by name and position and copy the raw bytes into a `Vec<u8>`.
+    ///
+    /// This returns the same bytes as `query_buffer` (newline characters are **not**
+    /// removed) but owned, so they can outlive the `IndexedFasta` or be sent to another
+    /// thread. Unlike `query_buffer` this also works on BGZF-compressed files and files
+    /// that are not memory-mapped.
+    ///
+    /// # Errors
+    ///
+    /// See `query_buffer`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx.query_buffer_owned("chr1", 20, 40).unwrap();
+    /// assert_eq!(seq, b"AGCTAGCT\nCATCGATCGTAC");
+    /// ```
+    pub fn query_buffer_owned(&self, name: &str, start: usize, end: usize) -> Result<Vec<u8>> {
+        Ok(self.fetch_interval(name, start, end)?.into_owned())
+    }
+
+    /// Query the FASTA file by a region string but do not copy to internal buffer.
+    ///
+    /// The region is given as `name:start-end` in 1-based inclusive coordinates, as used
+    /// by `samtools faidx`, and the raw slice is returned with newlines intact as in
+    /// `query_buffer`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the region is not of the form `name:start-end`.
+    /// - Error if the `start` position is `0` or greater than the `end` position.
+    /// - All errors of `query_buffer`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx.query_buffer_region("chr1:1-10").unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    ///
+    /// // Newlines are kept
+    /// let seq = faidx.query_buffer_region("chr1:21-40").unwrap();
+    /// assert_eq!(seq.len(), 21);
+    ///
+    /// assert!(faidx.query_buffer_region("chr1").is_err());
+    /// ```
+    pub fn query_buffer_region(&self, region: &str) -> Result<&[u8]> {
+        let (name, start, end) = parse_region(region)?;
+        let (start, end) = IntervalMode::Closed1.to_half_open(start, end)?;
+        self.query_buffer(name, start, end)
+    }
+
+    /// Query the FASTA file by name and position.
+    ///
+    /// The sequence is returned as a `&[u8]` slice but is not guaranteed to be valid UTF-8.
+    /// It also removes all line terminators (`\n` or `\r\n`) from the sequence slice.
+    ///
+    /// This method will truncate the sequence if the `end` position is greater than the sequence length
+    /// to avoid an error and only return the sequence up to the sequence length.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name`is not found in the index.
+    /// - Error if the `start` position is greater than the `end` position.
+    /// - Error if the `start` position is equal to the `end` position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Overextend the query into chr1 (which is 112 bases long)
+    /// let seq = faidx.query("chr1", 100, 120);
+    /// assert!(seq.is_err());
+    ///
+    /// // Overextend the query into chr1 but truncate the sequence
+    /// // with `query_unbounded`
+    /// let seq = faidx.query_unbounded("chr1", 100, 120).unwrap();
+    /// assert_eq!(seq.len(), 12);
+    /// ```
+    pub fn query_unbounded(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        self.fill_buffer(name, start, end, false)
+    }
+
+    /// Query the FASTA file with coordinates that may count back from the contig end.
+    ///
+    /// As with Python slices, a negative position `p` is resolved to `length + p`, so
+    /// `-1` is the last base and `-100..-1` is the 99 bases before it (the interval is
+    /// still half-open). Non-negative positions are used as given. The resolved interval
+    /// is validated and dispatched to `query`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if a negative position counts back past the start of the contig.
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr2 is 176bp and ends with AAACCACA
+    /// let seq = faidx.query_signed("chr2", -8, -1).unwrap();
+    /// assert_eq!(seq, b"AAACCAC");
+    ///
+    /// // The last 8 bases
+    /// let seq = faidx.query_signed("chr2", -8, 176).unwrap();
+    /// assert_eq!(seq, b"AAACCACA");
+    ///
+    /// assert!(faidx.query_signed("chr2", -200, -1).is_err());
+    /// ```
+    pub fn query_signed(&mut self, name: &str, start: i64, end: i64) -> Result<Sequence<'_>> {
+        let length = match self.entry(name) {
+            Some(entry) => entry.length,
+            None => bail!("No entry found for {}", name),
+        };
+        let resolve = |pos: i64| {
+            if pos >= 0 {
+                return Ok(pos as usize);
+            }
+            match length.checked_sub(pos.unsigned_abs() as usize) {
+                Some(pos) => Ok(pos),
+                None => bail!(
+                    "Position {} counts back past the start of {} ({} bases)",
+                    pos,
+                    name,
+                    length
+                ),
+            }
+        };
+        let (start, end) = (resolve(start)?, resolve(end)?);
+        self.query(name, start, end)
+    }
+
+    /// Query the FASTA file with a fixed-length result, padding out-of-bounds positions.
+    ///
+    /// This is the padded analog of `query_unbounded`: `start` may be negative and `end`
+    /// may exceed the sequence length, and every position outside the contig is filled
+    /// with `pad` (e.g. `b'N'`). The returned sequence always has `end - start` bases,
+    /// which is useful for fixed-width windows at contig edges (e.g. model inputs).
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the `start` position is not less than the `end` position.
+    /// - Error if applying the coordinate offset of the contig overflows `i64`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Three positions before the start of chr1
+    /// let seq = faidx.query_padded("chr1", -3, 5, b'N').unwrap();
+    /// assert_eq!(seq, b"NNNACCTA");
+    ///
+    /// // chr1 is 112bp
+    /// let seq = faidx.query_padded("chr1", 108, 115, b'N').unwrap();
+    /// assert_eq!(seq, b"GCGGNNN");
+    /// ```
+    pub fn query_padded(&mut self, name: &str, start: i64, end: i64, pad: u8) -> Result<&[u8]> {
+        let length = match self.entry(name) {
+            Some(entry) => entry.length as i64,
+            None => bail!("No entry found for {}", name),
+        };
+        if start >= end {
+            bail!("Start position must be less than end position");
+        }
+        let delta = self
+            .offsets
+            .get(self.canonical_name(name))
+            .copied()
+            .unwrap_or(0) as i64;
+        let (start, end) = match (start.checked_add(delta), end.checked_add(delta)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => bail!(
+                "Coordinate offset {} overflows the interval {}-{} of {}",
+                delta,
+                start,
+                end,
+                name
+            ),
+        };
+        let total = match end.checked_sub(start) {
+            Some(total) => total as usize,
+            None => bail!("Interval {}-{} of {} is too long", start, end, name),
+        };
+        let (seq_start, seq_end) = (start.clamp(0, length), end.clamp(0, length));
+
+        self.buffer.clear();
+        if seq_start < seq_end {
+            self.buffer.resize((seq_start - start) as usize, pad);
+            self.append_buffer(name, seq_start as usize, seq_end as usize, true)?;
+        }
+        // A window entirely outside the contig is all padding
+        self.buffer.resize(total, pad);
+        Ok(&self.buffer)
+    }
+
+    /// Query a circular contig, wrapping around its end back to position `0`.
+    ///
+    /// For circular genomes (e.g. bacterial chromosomes, plasmids, or mitochondria) the
+    /// `end` position may exceed the sequence length, in which case the bases beyond the
+    /// end are taken from the start of the contig and concatenated in the buffer.
+    /// Intervals that do not exceed the sequence length behave exactly like `query`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the `start` position is not less than the `end` position.
+    /// - Error if the `start` position is not less than the sequence length.
+    /// - Error if the interval is longer than the sequence length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr1 is 112bp: the last 4 bases followed by the first 4 bases
+    /// let seq = faidx.query_circular("chr1", 108, 116).unwrap();
+    /// assert_eq!(seq, b"GCGGACCT");
+    /// ```
+    pub fn query_circular(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        let length = match self.entry(name) {
+            Some(entry) => entry.length,
+            None => bail!("No entry found for {}", name),
+        };
+        if start >= end {
+            bail!("Start position must be less than end position");
+        }
+        if start >= length {
+            bail!("Start position must be less than sequence length");
+        }
+        if end - start > length {
+            bail!(
+                "Circular query of {} bases is longer than {} ({} bases)",
+                end - start,
+                name,
+                length
+            );
+        }
+        if end <= length {
+            return self.fill_buffer(name, start, end, true);
+        }
+        self.buffer.clear();
+        self.append_buffer(name, start, length, true)?;
+        self.append_buffer(name, 0, end - length, true)?;
+        Ok(&self.buffer)
+    }
+
+    /// Query the FASTA file by name and position.
+    ///
+    /// The sequence is returned as a `&[u8]` slice but is not guaranteed to be valid UTF-8.
+    /// This will **not** remove newline characters from the sequence slice.
+    ///
+    /// This method will truncate the sequence if the `end` position is greater than the sequence length
+    /// to avoid an error and only return the sequence up to the sequence length.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name`is not found in the index.
+    /// - Error if the `start` position is greater than the `end` position.
+    /// - Error if the `start` position is equal to the `end` position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Overextend the query into chr1 (which is 112 bases long)
+    /// let seq = faidx.query_buffer("chr1", 100, 120);
+    /// assert!(seq.is_err());
+    ///
+    /// // Overextend the query into chr1 but truncate the sequence
+    /// // with `query_unbounded`
+    /// let seq = faidx.query_buffer_unbounded("chr1", 100, 120).unwrap();
+    ///
+    /// // The sequence is truncated to 13 characters
+    /// assert_eq!(seq.len(), 13);
+    ///
+    /// // The sequence contains newline characters
+    /// assert!(seq.contains(&b'\n'));
+    ///
+    /// // The sequence contains 12 non-newline characters
+    /// assert_eq!(seq.iter().filter(|&&c| c != b'\n').count(), 12);
+    /// ```
+    pub fn query_buffer_unbounded(&self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        let (start, end) = self.shift_interval(name, start, end)?;
+        self.slice_interval(name, start, end, false)
+    }
+
+    /// Query the full sequence of a contig by name.
+    ///
+    /// The sequence is returned as a `&[u8]` slice but is not guaranteed to be valid UTF-8.
+    /// It also removes all line terminators (`\n` or `\r\n`) from the sequence slice.
+    ///
+    /// This is equivalent to calling `query` with the interval `0..length` where `length`
+    /// is the sequence length recorded in the index.
+    /// A contig without sequence (`length == 0`) returns an empty slice.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Query the full sequence of chr1 (which is 112 bases long)
+    /// let seq = faidx.query_all("chr1").unwrap();
+    /// assert_eq!(seq.len(), 112);
+    /// assert!(!seq.contains(&b'\n'));
+    /// ```
+    pub fn query_all(&mut self, name: &str) -> Result<&[u8]> {
+        let length = match self.entry(name) {
+            Some(entry) => entry.length,
+            None => bail!("No entry found for {}", name),
+        };
+        if length == 0 {
+            self.buffer.clear();
+            return Ok(&self.buffer);
+        }
+        self.fill_buffer(name, 0, length, true)
+    }
+
+    /// Returns a parallel iterator over the full sequence of every contig.
+    ///
+    /// Each item is the contig name and an owned copy of its sequence without newlines,
+    /// since the internal buffer cannot be borrowed from several threads at once.
+    /// Coordinate offsets are not applied, as in `query_all`.
+    ///
+    /// Every sequence in flight is held in memory at the same time (up to one per thread
+    /// while processing, and all of them if collected), so for large genomes prefer
+    /// consuming the items as they are produced or selecting contigs with
+    /// `par_contigs_filtered`.
+    ///
+    /// This requires the `rayon` feature.
+    ///
+    /// # Errors
+    ///
+    /// An item is an error if its sequence cannot be read from the FASTA file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    /// use rayon::prelude::*;
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let lengths = faidx
+    ///     .par_contigs()
+    ///     .map(|contig| contig.map(|(name, seq)| (name, seq.len())))
+    ///     .collect::<anyhow::Result<Vec<_>>>()
+    ///     .unwrap();
+    /// assert_eq!(lengths, vec![("chr1".to_string(), 112), ("chr2".to_string(), 176)]);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_contigs(
+        &self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = Result<(String, Vec<u8>)>> + '_ {
+        self.par_contigs_filtered(|_| true)
+    }
+
+    /// Returns a parallel iterator over the full sequence of the contigs matching a predicate.
+    ///
+    /// The `predicate` is evaluated on each `IndexEntry` before its sequence is read, so
+    /// unwanted contigs are never copied. Otherwise this is identical to `par_contigs`.
+    ///
+    /// This requires the `rayon` feature.
+    ///
+    /// # Errors
+    ///
+    /// An item is an error if its sequence cannot be read from the FASTA file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    /// use rayon::prelude::*;
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let contigs = faidx
+    ///     .par_contigs_filtered(|entry| entry.length > 150)
+    ///     .collect::<anyhow::Result<Vec<_>>>()
+    ///     .unwrap();
+    /// assert_eq!(contigs.len(), 1);
+    /// assert_eq!(contigs[0].0, "chr2");
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_contigs_filtered<F>(
+        &self,
+        predicate: F,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = Result<(String, Vec<u8>)>> + '_
+    where
+        F: Fn(&IndexEntry) -> bool,
+    {
+        use rayon::prelude::*;
+
+        let entries = self
+            .ordered_entries()
+            .into_iter()
+            .filter(|entry| predicate(entry))
+            .collect::<Vec<_>>();
+        entries.into_par_iter().map(move |entry| {
+            let mut seq = Vec::new();
+            if entry.length > 0 {
+                let query_pos = QueryPosition::new(0, entry.length, entry);
+                self.storage
+                    .extend_bases(&query_pos, &mut seq)
+                    .map_err(|e| read_error(&entry.name, e))?;
+            }
+            if self.uppercase {
+                seq.make_ascii_uppercase();
+            }
+            Ok((entry.name.clone(), seq))
+        })
+    }
+
+    /// Query the full sequence of a contig by name but do not copy to internal buffer.
+    ///
+    /// This will **not** remove newline characters from the sequence slice.
+    ///
+    /// This is equivalent to calling `query_buffer` with the interval `0..length` where
+    /// `length` is the sequence length recorded in the index.
+    /// A contig without sequence (`length == 0`) returns an empty slice.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Query the full sequence of chr1 (which is 112 bases over 4 lines)
+    /// let seq = faidx.query_buffer_all("chr1").unwrap();
+    /// assert_eq!(seq.iter().filter(|&&c| c != b'\n').count(), 112);
+    /// assert_eq!(seq.iter().filter(|&&c| c == b'\n').count(), 4);
+    /// ```
+    pub fn query_buffer_all(&self, name: &str) -> Result<&[u8]> {
+        let length = match self.entry(name) {
+            Some(entry) => entry.length,
+            None => bail!("No entry found for {}", name),
+        };
+        if length == 0 {
+            return Ok(&[]);
+        }
+        self.slice_interval(name, 0, length, true)
+    }
+
+    /// Query the FASTA file by name and position and convert the sequence to uppercase.
+    ///
+    /// This behaves like `query` (newlines are removed) but additionally ASCII-uppercases
+    /// the sequence in place, removing any soft-masking.
+    /// Only the bytes `a..=z` are changed so `N` and ambiguity codes are folded correctly
+    /// and any other bytes are left untouched.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the `start` position is greater than the `end` position.
+    /// - Error if the `start` position is equal to the `end` position.
+    /// - Error if the `end` position is greater than the index sequence length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/masked.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/masked.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // The soft-masking is preserved by `query`
+    /// let seq = faidx.query("chr1", 4, 12).unwrap();
+    /// assert_eq!(seq, b"ACGTacgt");
+    ///
+    /// // and removed by `query_uppercased`
+    /// let seq = faidx.query_uppercased("chr1", 4, 12).unwrap();
+    /// assert_eq!(seq, b"ACGTACGT");
+    /// ```
+    pub fn query_uppercased(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        self.query_buffered(name, start, end)?;
+        self.buffer.make_ascii_uppercase();
+        Ok(&self.buffer)
+    }
+
+    /// Query a region with an inner target in uppercase and its flanks in lowercase.
+    ///
+    /// The `outer` interval is queried as in `query` (newlines are removed), then the
+    /// bases within the `inner` interval are uppercased and all other bases are
+    /// lowercased. Both are 0-based half-open `(start, end)` intervals in contig
+    /// coordinates, and `inner` must lie within `outer`.
+    /// This is the soft-masked context expected by primer and probe design tools.
+    ///
+    /// # Errors
+    ///
+    /// - Error if `inner` is empty or not contained in `outer`.
+    /// - Error if the `outer` interval cannot be queried (see `query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACCTACGATC
+    /// let seq = faidx.query_with_mask("chr1", (0, 10), (3, 6)).unwrap();
+    /// assert_eq!(seq, b"accTACgatc");
+    /// ```
+    pub fn query_with_mask(
+        &mut self,
+        name: &str,
+        outer: (usize, usize),
+        inner: (usize, usize),
+    ) -> Result<&[u8]> {
+        let ((start, end), (inner_start, inner_end)) = (outer, inner);
+        if inner_start >= inner_end {
+            bail!(
+                "Inner interval start {} must be less than its end {}",
+                inner_start,
+                inner_end
+            );
+        }
+        if inner_start < start || inner_end > end {
+            bail!(
+                "Inner interval {}-{} is not contained in the outer interval {}-{}",
+                inner_start,
+                inner_end,
+                start,
+                end
+            );
+        }
+        self.query_buffered(name, start, end)?;
+        let (lo, hi) = (inner_start - start, inner_end - start);
+        self.buffer[..lo].make_ascii_lowercase();
+        self.buffer[lo..hi].make_ascii_uppercase();
+        self.buffer[hi..].make_ascii_lowercase();
+        Ok(&self.buffer)
+    }
+
+    /// Query the FASTA file by name and position and split the sequence at its midpoint.
+    ///
+    /// The sequence is queried with `query` (so newlines are removed) and returned as
+    /// the first and second half of the region. Both halves borrow from the internal buffer.
+    /// If the region has an odd length the extra base is placed in the first half.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the `start` position is greater than the `end` position.
+    /// - Error if the `start` position is equal to the `end` position.
+    /// - Error if the `end` position is greater than the index sequence length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // Split the first 9 bases of chr1
+    /// let (left, right) = faidx.query_halves("chr1", 0, 9).unwrap();
+    /// assert_eq!(left, b"ACCTA");
+    /// assert_eq!(right, b"CGAT");
+    /// ```
+    pub fn query_halves(&mut self, name: &str, start: usize, end: usize) -> Result<(&[u8], &[u8])> {
+        let seq = self.query(name, start, end)?;
+        let mid = seq.len().div_ceil(2);
+        Ok(seq.as_bytes().split_at(mid))
+    }
+
+    /// Query the first of several alternative contig names that succeeds.
+    ///
+    /// Each name in `names` is tried in order with `query` and the first successful
+    /// result is returned. This is useful when the same sequence may be keyed under
+    /// several identifiers (e.g. `chr1` and `1`).
+    ///
+    /// # Errors
+    ///
+    /// Error if `names` is empty or if the query fails for every name.
+    /// The error message includes the failure of each name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx.query_any(&["1", "chr1"], 0, 10).unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    ///
+    /// assert!(faidx.query_any(&["1", "chrom1"], 0, 10).is_err());
+    /// ```
+    pub fn query_any(&mut self, names: &[&str], start: usize, end: usize) -> Result<&[u8]> {
+        if names.is_empty() {
+            bail!("No names provided");
+        }
+        let mut errors = Vec::with_capacity(names.len());
+        for name in names {
+            match self.query_buffered(name, start, end) {
+                Ok(_) => return Ok(&self.buffer),
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            }
+        }
+        bail!(
+            "No alternative name could be queried ({})",
+            errors.join("; ")
+        )
+    }
+
+    /// Query many regions at once, concatenating them into a single buffer.
+    ///
+    /// Each region is a `(name, start, end)` tuple with the same semantics as `query`.
+    /// The newline-stripped sequences are appended to the internal buffer one after
+    /// another and the returned ranges delimit each result inside the buffer,
+    /// which can be accessed with `buffer` until the next query.
+    ///
+    /// # Errors
+    ///
+    /// If any region fails (see `query`) the whole call fails and the error message
+    /// includes the index of the offending region.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let ranges = faidx
+    ///     .query_many(&[("chr1", 0, 4), ("chr2", 0, 6)])
+    ///     .unwrap();
+    /// assert_eq!(ranges, vec![0..4, 4..10]);
+    /// assert_eq!(faidx.buffer(), b"ACCTTTTTGA");
+    /// assert_eq!(&faidx.buffer()[ranges[1].clone()], b"TTTTGA");
+    ///
+    /// // The index of a failing region is reported
+    /// let err = faidx.query_many(&[("chr1", 0, 4), ("chr3", 0, 4)]).unwrap_err();
+    /// assert!(err.to_string().contains("region 1"));
+    /// ```
+    pub fn query_many(&mut self, regions: &[(&str, usize, usize)]) -> Result<Vec<Range<usize>>> {
+        self.buffer.clear();
+        let mut ranges = Vec::with_capacity(regions.len());
+        for (idx, &(name, start, end)) in regions.iter().enumerate() {
+            let offset = self.buffer.len();
+            self.shift_interval(name, start, end)
+                .and_then(|(start, end)| self.append_buffer(name, start, end, true))
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to query region {} ({}:{}-{}): {}",
+                        idx,
+                        name,
+                        start,
+                        end,
+                        e
+                    )
+                })?;
+            ranges.push(offset..self.buffer.len());
+        }
+        Ok(ranges)
+    }
+
+    /// Query many regions and concatenate their sequences, optionally separated.
+    ///
+    /// Each region is a `(name, start, end)` tuple with the same semantics as `query`.
+    /// The newline-stripped sequences are appended to the internal buffer in order,
+    /// with the `sep` byte (if any) inserted between consecutive regions.
+    /// Regions may come from contigs with different line widths since each is read with
+    /// the geometry of its own `IndexEntry`, e.g. to build a spliced transcript from
+    /// exons.
+    ///
+    /// # Errors
+    ///
+    /// If any region fails (see `query`) the whole call fails and the error message
+    /// includes the index of the offending region.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let exons = [("chr1", 0, 4), ("chr1", 10, 14)];
+    /// assert_eq!(faidx.query_concat(&exons, None).unwrap(), b"ACCTGACT");
+    /// assert_eq!(faidx.query_concat(&exons, Some(b'N')).unwrap(), b"ACCTNGACT");
+    /// ```
+    pub fn query_concat(
+        &mut self,
+        regions: &[(&str, usize, usize)],
+        sep: Option<u8>,
+    ) -> Result<&[u8]> {
+        self.buffer.clear();
+        for (idx, &(name, start, end)) in regions.iter().enumerate() {
+            if let (Some(sep), true) = (sep, idx > 0) {
+                self.buffer.push(sep);
+            }
+            self.shift_interval(name, start, end)
+                .and_then(|(start, end)| self.append_buffer(name, start, end, true))
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to query region {} ({}:{}-{}): {}",
+                        idx,
+                        name,
+                        start,
+                        end,
+                        e
+                    )
+                })?;
+        }
+        Ok(&self.buffer)
+    }
+
+    /// Query overlapping intervals of a contig by fetching each merged span once.
+    ///
+    /// The half-open `(start, end)` intervals are sorted and overlapping or adjacent
+    /// intervals are merged before querying, so dense annotation sets read every base
+    /// at most once. Returns the merged intervals in order along with the
+    /// newline-stripped sequence of each, borrowed from the internal buffer.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// - Error if any interval is empty or has `start > end`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let (merged, seqs) = faidx
+    ///     .query_merged("chr1", &[(4, 10), (0, 6), (20, 24)])
+    ///     .unwrap();
+    /// assert_eq!(merged, vec![(0, 10), (20, 24)]);
+    /// assert_eq!(seqs, vec![&b"ACCTACGATC"[..], &b"AGCT"[..]]);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn query_merged(
+        &mut self,
+        name: &str,
+        intervals: &[(usize, usize)],
+    ) -> Result<(Vec<(usize, usize)>, Vec<&[u8]>)> {
+        for &(start, end) in intervals {
+            if start >= end {
+                bail!("Interval start {} must be less than its end {}", start, end);
+            }
+        }
+        let merged = merge_intervals(intervals);
+
+        self.buffer.clear();
+        let mut ranges = Vec::with_capacity(merged.len());
+        for &(start, end) in &merged {
+            let offset = self.buffer.len();
+            let (start, end) = self.shift_interval(name, start, end)?;
+            self.append_buffer(name, start, end, true)?;
+            ranges.push(offset..self.buffer.len());
+        }
+        let seqs = ranges
+            .into_iter()
+            .map(|range| &self.buffer[range])
+            .collect();
+        Ok((merged, seqs))
+    }
+
+    /// Query many regions, yielding a separate result for each one.
+    ///
+    /// Each region is a `(name, start, end)` tuple with the same semantics as `query`,
+    /// and the iterator yields an owned, newline-stripped copy of its sequence. Unlike
+    /// `query_many`, an invalid region does not abort the batch: it produces an error
+    /// item naming the region and the iteration continues with the next region, so the
+    /// valid regions of a noisy annotation can be kept.
+    ///
+    /// Regions are queried lazily as the iterator is advanced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let regions = [("chr1", 0, 10), ("chr3", 0, 10), ("chr2", 0, 10)];
+    /// let results = faidx.query_each(regions).collect::<Vec<_>>();
+    /// assert_eq!(results[0].as_ref().unwrap(), b"ACCTACGATC");
+    /// assert!(results[1].is_err());
+    /// assert_eq!(results[2].as_ref().unwrap(), b"TTTTGATCGA");
+    /// ```
+    pub fn query_each<'a, I>(&'a mut self, regions: I) -> impl Iterator<Item = Result<Vec<u8>>> + 'a
+    where
+        I: IntoIterator<Item = (&'a str, usize, usize)>,
+        I::IntoIter: 'a,
+    {
+        regions
+            .into_iter()
+            .enumerate()
+            .map(
+                move |(idx, (name, start, end))| match self.query(name, start, end) {
+                    Ok(seq) => Ok(seq.to_vec()),
+                    Err(e) => Err(anyhow!(
+                        "Failed to query region {} ({}:{}-{}): {}",
+                        idx,
+                        name,
+                        start,
+                        end,
+                        e
+                    )),
+                },
+            )
+    }
+
+    /// Query the sequence of every interval of a BED file.
+    ///
+    /// Each line is parsed as BED3 (`chrom`, `start`, `end`, tab-separated and 0-based
+    /// half-open, matching this crate's coordinates), additional columns are ignored,
+    /// and blank, comment (`#`), `track`, and `browser` lines are skipped.
+    /// The iterator yields `(name, start, end, sequence)` with an owned, newline-stripped
+    /// sequence (as `query`) for each interval.
+    ///
+    /// # Errors
+    ///
+    /// A line that cannot be read, parsed, or queried produces an error item naming the
+    /// line number and the iteration continues with the next line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let bed = "chr1\t0\t10\nchr2\t0\t10\tname\n";
+    /// let seqs = faidx
+    ///     .query_bed(bed.as_bytes())
+    ///     .map(|record| record.map(|(_, _, _, seq)| seq))
+    ///     .collect::<anyhow::Result<Vec<_>>>()
+    ///     .unwrap();
+    /// assert_eq!(seqs, vec![b"ACCTACGATC".to_vec(), b"TTTTGATCGA".to_vec()]);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn query_bed<'a, R: Read + 'a>(
+        &'a mut self,
+        reader: R,
+    ) -> impl Iterator<Item = Result<(String, usize, usize, Vec<u8>)>> + 'a {
+        BufReader::new(reader)
+            .lines()
+            .enumerate()
+            .filter_map(move |(idx, line)| {
+                let record = line
+                    .map_err(anyhow::Error::from)
+                    .and_then(|line| parse_bed_line(&line))
+                    .and_then(|interval| match interval {
+                        Some((name, start, end)) => {
+                            let seq = self.query(&name, start, end)?.to_vec();
+                            Ok(Some((name, start, end, seq)))
+                        }
+                        None => Ok(None),
+                    });
+                match record {
+                    Ok(record) => record.map(Ok),
+                    Err(e) => Some(Err(anyhow!("BED line {}: {}", idx + 1, e))),
+                }
+            })
+    }
+
+    /// Returns the contents of the internal buffer.
+    ///
+    /// This holds the result of the last buffered query (e.g. `query` or `query_many`).
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Query the FASTA file and translate the sequence to protein.
+    ///
+    /// The newline-stripped sequence of the interval (as `query`) is read from `frame`
+    /// bases after `start` (`0`, `1`, or `2`) and translated codon by codon with the
+    /// standard genetic code. Stop codons are translated to `*` and codons containing
+    /// `N` or any other ambiguous base to `X`. A partial trailing codon is dropped.
+    ///
+    /// # Errors
+    ///
+    /// - Error if `frame` is greater than `2`.
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACC TAC GAT CGA
+    /// let protein = faidx.query_translated("chr1", 0, 12, 0).unwrap();
+    /// assert_eq!(protein, b"TYDR");
+    /// ```
+    pub fn query_translated(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+        frame: u8,
+    ) -> Result<Vec<u8>> {
+        if frame > 2 {
+            bail!("Reading frame must be 0, 1, or 2 (found {})", frame);
+        }
+        let seq = self.query(name, start, end)?;
+        Ok(translate(seq.get(frame as usize..).unwrap_or_default()))
+    }
+
+    /// Query the FASTA file and iterate over the codons of the sequence.
+    ///
+    /// The newline-stripped sequence of the interval (as `query`) is read once and the
+    /// returned iterator yields consecutive codons starting `frame` bases after `start`
+    /// (`0`, `1`, or `2`), without allocating a codon at a time.
+    /// One or two trailing bases that do not form a full codon are dropped.
+    ///
+    /// # Errors
+    ///
+    /// - Error if `frame` is greater than `2`.
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // A CCT ACG ATC G
+    /// let codons = faidx.codons("chr1", 0, 11, 1).unwrap().collect::<Vec<_>>();
+    /// assert_eq!(codons, vec![*b"CCT", *b"ACG", *b"ATC"]);
+    /// ```
+    pub fn codons(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+        frame: u8,
+    ) -> Result<impl Iterator<Item = [u8; 3]> + '_> {
+        if frame > 2 {
+            bail!("Reading frame must be 0, 1, or 2 (found {})", frame);
+        }
+        let seq = self.query(name, start, end)?.as_bytes();
+        Ok(seq
+            .get(frame as usize..)
+            .unwrap_or_default()
+            .chunks_exact(3)
+            .map(|codon| [codon[0], codon[1], codon[2]]))
+    }
+
+    /// Query the FASTA file and return the sequence packed to 2 bits per base.
+    ///
+    /// Bases are encoded as `A = 00`, `C = 01`, `G = 10`, and `T = 11` (case-insensitive)
+    /// and packed four to a byte, the first base in the two most significant bits.
+    /// The unused low bits of a final partial byte are zero, so the number of bases is
+    /// returned alongside the packed bytes.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the sequence contains any base other than `A`, `C`, `G`, or `T`
+    ///   (e.g. `N` or an IUPAC ambiguity code), which cannot be represented in 2 bits.
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACCT AC
+    /// let (packed, len) = faidx.query_packed("chr1", 0, 6).unwrap();
+    /// assert_eq!(len, 6);
+    /// assert_eq!(packed, vec![0b00_01_01_11, 0b00_01_00_00]);
+    /// ```
+    pub fn query_packed(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(Vec<u8>, usize)> {
+        let seq = self.query(name, start, end)?;
+        let mut packed = vec![0; seq.len().div_ceil(4)];
+        for (i, &c) in seq.iter().enumerate() {
+            let code = match c.to_ascii_uppercase() {
+                b'A' => 0b00,
+                b'C' => 0b01,
+                b'G' => 0b10,
+                b'T' => 0b11,
+                _ => bail!(
+                    "Cannot pack base {} at position {} of {} in 2 bits",
+                    c as char,
+                    start + i,
+                    name
+                ),
+            };
+            packed[i / 4] |= code << (6 - 2 * (i % 4));
+        }
+        Ok((packed, seq.len()))
+    }
+
+    /// Test whether two intervals of the same contig are reverse complements of each other.
+    ///
+    /// The interval `a` is compared against the reverse complement of the interval `b`.
+    /// Both intervals are read directly from the memory map, skipping newline characters,
+    /// so no sequence is copied for uncompressed files. The comparison is case-insensitive.
+    ///
+    /// Intervals of different lengths are never reverse complements and return `false`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if either interval is malformed or out of bounds (see `query`).
+    /// - Error if either interval contains a byte that is not a nucleotide or IUPAC
+    ///   ambiguity code (e.g. protein sequence).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // CGAT (5..9) and ATCG (7..11) are reverse complements
+    /// assert!(faidx.is_inverted_repeat("chr1", (5, 9), (7, 11)).unwrap());
+    ///
+    /// // Intervals of different lengths are never reverse complements
+    /// assert!(!faidx.is_inverted_repeat("chr1", (5, 9), (7, 12)).unwrap());
+    /// ```
+    pub fn is_inverted_repeat(
+        &self,
+        name: &str,
+        a: (usize, usize),
+        b: (usize, usize),
+    ) -> Result<bool> {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        let a = self.shift_interval(name, a.0, a.1)?;
+        let b = self.shift_interval(name, b.0, b.1)?;
+        validate_interval(entry, a.0, a.1, true)?;
+        validate_interval(entry, b.0, b.1, true)?;
+        if a.1 - a.0 != b.1 - b.0 {
+            return Ok(false);
+        }
+        let pos_a = QueryPosition::new(a.0, a.1, entry);
+        let pos_b = QueryPosition::new(b.0, b.1, entry);
+        let slice_a = self
+            .storage
+            .fetch_query(&pos_a)
+            .map_err(|e| read_error(name, e))?;
+        let slice_b = self
+            .storage
+            .fetch_query(&pos_b)
+            .map_err(|e| read_error(name, e))?;
+        ensure_nucleotide(&slice_a, name)?;
+        ensure_nucleotide(&slice_b, name)?;
+        let forward = slice_a.iter().filter(|&&c| !is_line_break(c));
+        let reverse = slice_b
+            .iter()
+            .rev()
+            .filter(|&&c| !is_line_break(c))
+            .map(|&c| DNA_COMPLEMENT[c as usize]);
+        Ok(forward
+            .zip(reverse)
+            .all(|(x, y)| x.eq_ignore_ascii_case(&y)))
+    }
+
+    /// Write an interval as a FASTA record to a writer.
+    ///
+    /// A `>header` line is written followed by the newline-stripped sequence of the
+    /// interval rewrapped to `line_width` bases per line. Each line (including the last)
+    /// is terminated by `\n`. This is useful for building subset references or panels.
+    ///
+    /// # Errors
+    ///
+    /// - Error if `line_width` is zero.
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    /// - Error if writing to `out` fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let mut out = Vec::new();
+    /// faidx
+    ///     .write_region("chr1", 0, 10, 4, "chr1:0-10", &mut out)
+    ///     .unwrap();
+    /// assert_eq!(out, b">chr1:0-10\nACCT\nACGA\nTC\n");
+    /// ```
+    pub fn write_region<W: Write>(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+        line_width: usize,
+        header: &str,
+        mut out: W,
+    ) -> Result<()> {
+        if line_width == 0 {
+            bail!("Line width must be greater than zero");
+        }
+        let seq = self.query(name, start, end)?;
+        writeln!(out, ">{}", header)?;
+        for line in seq.chunks(line_width) {
+            out.write_all(line)?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Write the full sequence of a contig as a FASTA record to a writer.
+    ///
+    /// A `>name` header line is written followed by the sequence of the contig (as
+    /// `query_all`) rewrapped to `line_width` bases per line, so a contig shorter than
+    /// `line_width` is written on a single line. Each line (including the last) is
+    /// terminated by `\n`, and a contig without sequence is written as a header alone.
+    /// The header holds the contig name from the index even if `name` is an alias.
+    ///
+    /// This is the whole-contig counterpart of `write_region`, e.g. to extract a single
+    /// chromosome into a standalone file.
+    ///
+    /// # Errors
+    ///
+    /// - Error if `line_width` is zero.
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if writing to `out` fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let mut out = Vec::new();
+    /// faidx.emit_contig("chr1", 60, &mut out).unwrap();
+    /// let lines = out.split(|&c| c == b'\n').map(|l| l.len()).collect::<Vec<_>>();
+    /// assert_eq!(lines, vec![5, 60, 52, 0]);
+    ///
+    /// // A contig shorter than the line width is written on a single line
+    /// out.clear();
+    /// faidx.emit_contig("chr2", 200, &mut out).unwrap();
+    /// assert_eq!(out.iter().filter(|&&c| c == b'\n').count(), 2);
+    /// ```
+    pub fn emit_contig<W: Write>(
+        &mut self,
+        name: &str,
+        line_width: usize,
+        mut out: W,
+    ) -> Result<()> {
+        if line_width == 0 {
+            bail!("Line width must be greater than zero");
+        }
+        let contig = match self.entry(name) {
+            Some(entry) => entry.name.clone(),
+            None => bail!("No entry found for {}", name),
+        };
+        let seq = self.query_all(name)?;
+        writeln!(out, ">{}", contig)?;
+        for line in seq.chunks(line_width) {
+            out.write_all(line)?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Write the reverse complement of an interval to a writer.
+    ///
+    /// The interval is walked backwards directly from the memory map and each
+    /// non-newline base is complemented (preserving case) into a small fixed-size chunk
+    /// which is written to `out` whenever it fills up. No buffer proportional to the
+    /// interval length is allocated for uncompressed files.
+    ///
+    /// Bases without a complement (e.g. IUPAC ambiguity codes) are written unchanged.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    /// - Error if the interval contains a byte that is not a nucleotide or IUPAC
+    ///   ambiguity code (e.g. protein sequence).
+    /// - Error if writing to `out` fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACCTACGATC
+    /// let mut out = Vec::new();
+    /// faidx.write_region_revcomp("chr1", 0, 10, &mut out).unwrap();
+    /// assert_eq!(out, b"GATCGTAGGT");
+    /// ```
+    pub fn write_region_revcomp<W: Write>(
+        &self,
+        name: &str,
+        start: usize,
+        end: usize,
+        out: &mut W,
+    ) -> Result<()> {
+        let seq_slice = self.fetch_interval(name, start, end)?;
+        ensure_nucleotide(&seq_slice, name)?;
+        let mut chunk = [0; REVCOMP_CHUNK_SIZE];
+        let mut filled = 0;
+        for &c in seq_slice.iter().rev().filter(|&&c| !is_line_break(c)) {
+            chunk[filled] = DNA_COMPLEMENT[c as usize];
+            filled += 1;
+            if filled == REVCOMP_CHUNK_SIZE {
+                out.write_all(&chunk)?;
+                filled = 0;
+            }
+        }
+        out.write_all(&chunk[..filled])?;
+        Ok(())
+    }
+
+    /// Query the reverse complement of an interval into the internal buffer.
+    ///
+    /// The newline-stripped sequence (as `query`) is reversed and complemented with
+    /// `DNA_COMPLEMENT`. Use `query_revcomp_with` for other complement tables.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the interval contains a byte that is not a nucleotide or IUPAC
+    ///   ambiguity code (e.g. protein sequence).
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACCTACGATC
+    /// let seq = faidx.query_revcomp("chr1", 0, 10).unwrap();
+    /// assert_eq!(seq, b"GATCGTAGGT");
+    /// ```
+    pub fn query_revcomp(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        self.query_buffered(name, start, end)?;
+        ensure_nucleotide(&self.buffer, name)?;
+        Ok(self.revcomp_buffer(&DNA_COMPLEMENT))
+    }
+
+    /// Query an interval on a given strand into the internal buffer.
+    ///
+    /// `Strand::Plus` returns the newline-stripped sequence (as `query`) and
+    /// `Strand::Minus` its reverse complement (as `query_revcomp`), so features with a
+    /// strand can be extracted without branching on it. The coordinates are always
+    /// forward-strand coordinates.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the strand is `Strand::Minus` and the interval contains a byte that is
+    ///   not a nucleotide or IUPAC ambiguity code (e.g. protein sequence).
+    /// - All errors of `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta, Strand};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACCTACGATC
+    /// let seq = faidx.query_stranded("chr1", 0, 10, Strand::Plus).unwrap();
+    /// assert_eq!(seq, b"ACCTACGATC");
+    /// let seq = faidx.query_stranded("chr1", 0, 10, Strand::Minus).unwrap();
+    /// assert_eq!(seq, b"GATCGTAGGT");
+    /// ```
+    pub fn query_stranded(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+        strand: Strand,
+    ) -> Result<&[u8]> {
+        match strand {
+            Strand::Plus => self.query_buffered(name, start, end),
+            Strand::Minus => self.query_revcomp(name, start, end),
+        }
+    }
+
+    /// Query the reverse complement of an interval with a custom complement table.
+    ///
+    /// The newline-stripped sequence (as `query`) is reversed and each byte `c` is
+    /// replaced by `table[c]`. The crate provides `DNA_COMPLEMENT`, `RNA_COMPLEMENT`
+    /// (`A` and `U`), and `IUPAC_COMPLEMENT` (including the ambiguity codes), and any
+    /// other alphabet can supply its own table. The sequence is not checked against the
+    /// table's alphabet.
+    ///
+    /// # Errors
+    ///
+    /// See `query`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta, IUPAC_COMPLEMENT};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let seq = faidx
+    ///     .query_revcomp_with("chr1", 0, 10, &IUPAC_COMPLEMENT)
+    ///     .unwrap();
+    /// assert_eq!(seq, b"GATCGTAGGT");
+    /// ```
+    pub fn query_revcomp_with(
+        &mut self,
+        name: &str,
+        start: usize,
+        end: usize,
+        table: &[u8; 256],
+    ) -> Result<&[u8]> {
+        self.query_buffered(name, start, end)?;
+        Ok(self.revcomp_buffer(table))
+    }
+
+    /// Reverse and complement the internal buffer in place with a complement table.
+    fn revcomp_buffer(&mut self, table: &[u8; 256]) -> &[u8] {
+        self.buffer.reverse();
+        for c in self.buffer.iter_mut() {
+            *c = table[*c as usize];
+        }
+        &self.buffer
+    }
+
+    /// Preview the first and last `n` bases of every contig.
+    ///
+    /// Returns a `(name, head, tail)` tuple for each contig in the order it appears in
+    /// the FASTA file. Newline characters are removed from both `head` and `tail`.
+    ///
+    /// If a contig is shorter than `2n` bases the `head` and `tail` are clamped so that
+    /// they do not overlap: the `head` takes up to `n` bases from the start and the `tail`
+    /// takes whatever remains of the last `n` bases.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let preview = faidx.preview(4).unwrap();
+    /// assert_eq!(preview.len(), 2);
+    ///
+    /// let (name, head, tail) = &preview[0];
+    /// assert_eq!(name, "chr1");
+    /// assert_eq!(head, b"ACCT");
+    /// assert_eq!(tail, b"GCGG");
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn preview(&mut self, n: usize) -> Result<Vec<(String, Vec<u8>, Vec<u8>)>> {
+        let contigs = self
+            .ordered_entries()
+            .into_iter()
+            .map(|entry| (entry.name.clone(), entry.length))
+            .collect::<Vec<_>>();
+
+        let mut preview = Vec::with_capacity(contigs.len());
+        for (name, length) in contigs {
+            let head_end = n.min(length);
+            let tail_start = length.saturating_sub(n).max(head_end);
+            let head = if head_end > 0 {
+                self.fill_buffer(&name, 0, head_end, true)?.to_vec()
+            } else {
+                Vec::new()
+            };
+            let tail = if tail_start < length {
+                self.fill_buffer(&name, tail_start, length, true)?.to_vec()
+            } else {
+                Vec::new()
+            };
+            preview.push((name, head, tail));
+        }
+        Ok(preview)
+    }
+
+    /// Split the reference into `n` partitions of roughly equal total length.
+    ///
+    /// Each partition is a list of `(name, start, end)` chunks and receives about
+    /// `total_length / n` bases. Contigs are packed greedily in the order they appear in
+    /// the FASTA file, and a contig is split across partitions whenever it overflows one.
+    ///
+    /// Exactly `n` partitions are returned, though trailing partitions may be empty if
+    /// the reference is shorter than `n` bases. If `n` is `0` no partitions are returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr1 is 112bp and chr2 is 176bp
+    /// let partitions = faidx.partition(2);
+    /// assert_eq!(partitions[0], vec![("chr1".to_string(), 0, 112), ("chr2".to_string(), 0, 32)]);
+    /// assert_eq!(partitions[1], vec![("chr2".to_string(), 32, 176)]);
+    /// ```
+    pub fn partition(&self, n: usize) -> Vec<Vec<(String, usize, usize)>> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let entries = self.ordered_entries();
+        let total = entries.iter().map(|entry| entry.length).sum::<usize>();
+        let target = total.div_ceil(n).max(1);
+
+        let mut partitions = vec![Vec::new(); n];
+        let mut bin = 0;
+        let mut filled = 0;
+        for entry in entries {
+            let mut start = 0;
+            while start < entry.length {
+                let remaining = entry.length - start;
+                // The last partition absorbs any remainder
+                let take = if bin + 1 == n {
+                    remaining
+                } else {
+                    remaining.min(target - filled)
+                };
+                partitions[bin].push((entry.name.clone(), start, start + take));
+                start += take;
+                filled += take;
+                if filled >= target && bin + 1 < n {
+                    bin += 1;
+                    filled = 0;
+                }
+            }
+        }
+        partitions
+    }
+
+    /// Create a cursor over fixed-size windows of a contig.
+    ///
+    /// Windows of `size` bases start every `step` bases, so a `step` smaller than `size`
+    /// yields overlapping windows and a larger `step` skips bases between windows.
+    /// The last window is truncated at the contig end rather than dropped.
+    ///
+    /// The cursor is advanced with `WindowCursor::next_window`, which borrows this
+    /// `IndexedFasta` to read each window into the internal buffer.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if `size` or `step` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr1 is 112bp
+    /// let mut cursor = faidx.windows("chr1", 50, 50).unwrap();
+    /// let mut lengths = Vec::new();
+    /// while let Some(window) = cursor.next_window(&mut faidx) {
+    ///     let (_start, seq) = window.unwrap();
+    ///     lengths.push(seq.len());
+    /// }
+    /// assert_eq!(lengths, vec![50, 50, 12]);
+    /// ```
+    pub fn windows(&self, name: &str, size: usize, step: usize) -> Result<WindowCursor> {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        if size == 0 || step == 0 {
+            bail!("Window size and step must be greater than zero");
+        }
+        Ok(WindowCursor::new(name, entry.length, size, step))
+    }
+
+    /// Returns the fraction of a contig that is queryable.
+    ///
+    /// A base is queryable if it is uppercase (not soft-masked) and not an `N`.
+    /// Both exclusions are computed in a single streaming pass over the contig,
+    /// skipping newline characters, without copying the sequence for uncompressed files.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the contig is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/masked.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/masked.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // 5 of the 25 bases of chr2 are uppercase and not N
+    /// assert_eq!(faidx.queryable_fraction("chr2").unwrap(), 0.2);
+    /// ```
+    pub fn queryable_fraction(&self, name: &str) -> Result<f64> {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        if entry.length == 0 {
+            bail!(
+                "Cannot compute the queryable fraction of empty contig {}",
+                name
+            );
+        }
+        let query_pos = QueryPosition::new(0, entry.length, entry);
+        let seq_slice = self
+            .storage
+            .fetch_query(&query_pos)
+            .map_err(|e| read_error(name, e))?;
+        let queryable = seq_slice
+            .iter()
+            .filter(|&&c| c.is_ascii_uppercase() && c != b'N')
+            .count();
+        Ok(queryable as f64 / entry.length as f64)
+    }
+
+    /// Returns the GC content of an interval.
+    ///
+    /// This is the fraction of bases in `start..end` that are `G` or `C` (case-insensitive).
+    /// The interval is read directly from the memory map, skipping newline characters,
+    /// so no buffer is allocated for uncompressed files.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // TTTTGATCGA
+    /// assert_eq!(faidx.gc_content("chr2", 0, 10).unwrap(), 0.3);
+    /// ```
+    pub fn gc_content(&self, name: &str, start: usize, end: usize) -> Result<f64> {
+        self.gc_content_with(name, start, end, &CompositionOpts::default())
+    }
+
+    /// Returns the GC content of an interval with composition options.
+    ///
+    /// This is `gc_content` except that with `CompositionOpts::skip_gaps` the gap bytes
+    /// (e.g. `-` in an alignment) are excluded from both the GC count and the total,
+    /// so the result is the GC content of the ungapped sequence.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the interval only contains gaps.
+    /// - All errors of `gc_content`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{CompositionOpts, FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/gapped.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/gapped.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACGT--ACGT
+    /// assert_eq!(faidx.gc_content("seq1", 0, 10).unwrap(), 0.4);
+    /// let opts = CompositionOpts::skip_gaps();
+    /// assert_eq!(faidx.gc_content_with("seq1", 0, 10, &opts).unwrap(), 0.5);
+    /// ```
+    pub fn gc_content_with(
+        &self,
+        name: &str,
+        start: usize,
+        end: usize,
+        opts: &CompositionOpts,
+    ) -> Result<f64> {
+        let seq_slice = self.fetch_interval(name, start, end)?;
+        let (gc, total) = seq_slice
+            .iter()
+            .filter(|&&c| !is_line_break(c) && !opts.is_skipped(c))
+            .fold((0usize, 0usize), |(gc, total), &c| {
+                let is_gc = matches!(c, b'G' | b'C' | b'g' | b'c');
+                (gc + is_gc as usize, total + 1)
+            });
+        if total == 0 {
+            bail!("Interval {}:{}-{} only contains gaps", name, start, end);
+        }
+        Ok(gc as f64 / total as f64)
+    }
+
+    /// Returns the soft-masked fraction of an interval.
+    ///
+    /// This is the fraction of bases in `start..end` that are lowercase ASCII letters,
+    /// which mark repeats in soft-masked assemblies (e.g. `acgt` and `n`).
+    /// The interval is read directly from the memory map, skipping newline characters,
+    /// so the sequence is neither copied nor transformed for uncompressed files.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/masked.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/masked.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACGTACGTacgtacgt
+    /// assert_eq!(faidx.masked_fraction("chr1", 0, 16).unwrap(), 0.5);
+    /// ```
+    pub fn masked_fraction(&self, name: &str, start: usize, end: usize) -> Result<f64> {
+        let seq_slice = self.fetch_interval(name, start, end)?;
+        let (masked, total) = seq_slice
+            .iter()
+            .filter(|&&c| !is_line_break(c))
+            .fold((0usize, 0usize), |(masked, total), &c| {
+                (masked + c.is_ascii_lowercase() as usize, total + 1)
+            });
+        Ok(masked as f64 / total as f64)
+    }
+
+    /// Returns the GC content of every window of a contig.
+    ///
+    /// Windows of `window` bases start every `step` bases from the start of the contig,
+    /// as with `windows`, and the last window is truncated at the contig end.
+    /// Each value is the fraction of bases in the window that are `G` or `C`
+    /// (case-insensitive), as with `gc_content`.
+    ///
+    /// The contig is read in a single pass with a rolling count: bases entering a window
+    /// are added and bases leaving it are removed, so overlapping windows do not rescan
+    /// their shared bases. Coordinate offsets are not applied, as in `query_all`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if `window` or `step` is zero.
+    /// - Error if the sequence cannot be read from the FASTA file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr1 is 112bp so the last window holds 12 bases
+    /// let track = faidx.gc_track("chr1", 50, 50).unwrap();
+    /// assert_eq!(track.len(), 3);
+    /// assert_eq!(track[0], faidx.gc_content("chr1", 0, 50).unwrap());
+    /// assert_eq!(track[2], faidx.gc_content("chr1", 100, 112).unwrap());
+    /// ```
+    pub fn gc_track(&self, name: &str, window: usize, step: usize) -> Result<Vec<f64>> {
+        let mut cursor = self.windows(name, window, step)?;
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        if entry.length == 0 {
+            return Ok(Vec::new());
+        }
+        let query_pos = QueryPosition::new(0, entry.length, entry);
+        let seq_slice = self
+            .storage
+            .fetch_query(&query_pos)
+            .map_err(|e| read_error(name, e))?;
+        let is_gc = |c: &u8| matches!(c, b'G' | b'C' | b'g' | b'c');
+        let mut bases = seq_slice.iter().filter(|&&c| !is_line_break(c));
+        let mut trailing = bases.clone();
+
+        let mut track = Vec::new();
+        let (mut lead, mut trail, mut gc) = (0, 0, 0);
+        while let Some((start, end)) = cursor.next_interval() {
+            // Add the bases entering the window, then remove the bases leaving it
+            for c in bases.by_ref().take(end - lead) {
+                gc += is_gc(c) as usize;
+            }
+            for c in trailing.by_ref().take(start - trail) {
+                gc -= is_gc(c) as usize;
+            }
+            (lead, trail) = (end, start);
+            track.push(gc as f64 / (end - start) as f64);
+        }
+        Ok(track)
+    }
+
+    /// Returns the base composition of an interval.
+    ///
+    /// Counts are case-folded (soft-masked bases are counted with their uppercase
+    /// counterpart) and every base that is not one of `ACGTN` is counted as `other`.
+    /// The interval is read directly from the memory map, skipping newline characters,
+    /// so the counts always sum to the interval length.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // TTTTGATCGA
+    /// let counts = faidx.base_counts("chr2", 0, 10).unwrap();
+    /// assert_eq!(counts.t, 5);
+    /// assert_eq!(counts.g, 2);
+    /// assert_eq!(counts.total(), 10);
+    /// ```
+    pub fn base_counts(&self, name: &str, start: usize, end: usize) -> Result<BaseCounts> {
+        self.base_counts_with(name, start, end, &CompositionOpts::default())
+    }
+
+    /// Returns the base composition of an interval with composition options.
+    ///
+    /// This is `base_counts` except that with `CompositionOpts::skip_gaps` the gap bytes
+    /// are not tallied at all (rather than as `other`), so the counts sum to the number
+    /// of ungapped bases in the interval.
+    ///
+    /// # Errors
+    ///
+    /// See `base_counts`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{CompositionOpts, FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/gapped.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/gapped.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // ACGT--ACGT
+    /// assert_eq!(faidx.base_counts("seq1", 0, 10).unwrap().other, 2);
+    /// let counts = faidx
+    ///     .base_counts_with("seq1", 0, 10, &CompositionOpts::skip_gaps())
+    ///     .unwrap();
+    /// assert_eq!(counts.other, 0);
+    /// assert_eq!(counts.total(), 8);
+    /// ```
+    pub fn base_counts_with(
+        &self,
+        name: &str,
+        start: usize,
+        end: usize,
+        opts: &CompositionOpts,
+    ) -> Result<BaseCounts> {
+        let seq_slice = self.fetch_interval(name, start, end)?;
+        let mut counts = BaseCounts::default();
+        seq_slice
+            .iter()
+            .filter(|&&c| !is_line_break(c) && !opts.is_skipped(c))
+            .for_each(|&c| counts.add(c));
+        Ok(counts)
+    }
+
+    /// Returns the checksum of an interval.
+    ///
+    /// The interval is uppercased and stripped of newline characters before hashing,
+    /// following the GA4GH refget convention, so soft-masking does not change the checksum.
+    /// The sequence is streamed into the hasher directly from the memory map without
+    /// materializing the stripped sequence.
+    ///
+    /// - `ChecksumAlgo::Md5` returns a lowercase hex string.
+    /// - `ChecksumAlgo::Sha512t24` returns the unpadded URL-safe base64 refget digest
+    ///   (without the `SQ.` prefix of a refget identifier).
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{ChecksumAlgo, FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let md5 = faidx.checksum("chr1", 0, 112, ChecksumAlgo::Md5).unwrap();
+    /// assert_eq!(md5, "b7527ac276543d14e98f6e41bdce3b51");
+    ///
+    /// let refget = faidx.checksum("chr1", 0, 112, ChecksumAlgo::Sha512t24).unwrap();
+    /// assert_eq!(refget, "FVKZ6BTkpPIG5TujDau_cy1HMUcxX0Yw");
+    /// ```
+    pub fn checksum(
+        &self,
+        name: &str,
+        start: usize,
+        end: usize,
+        algo: ChecksumAlgo,
+    ) -> Result<String> {
+        let seq_slice = self.fetch_interval(name, start, end)?;
+        Ok(checksum(&seq_slice, algo))
+    }
+
+    /// Returns the DUST low-complexity score of an interval.
+    ///
+    /// This is the SDUST triplet-frequency score computed over the newline-stripped
+    /// interval: `sum(c_t * (c_t - 1) / 2) / (l - 1)`, where `c_t` is the number of
+    /// occurrences of the triplet `t` and `l` is the number of triplets in the interval.
+    /// Regions dominated by a few triplets score high (low complexity).
+    ///
+    /// Bases are case-folded and triplets containing a base other than `ACGT` (e.g. `N`)
+    /// are skipped. Intervals with fewer than two triplets score `0.0`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // TTTT has two TTT triplets
+    /// assert_eq!(faidx.dust_score("chr2", 0, 4).unwrap(), 1.0);
+    /// ```
+    pub fn dust_score(&mut self, name: &str, start: usize, end: usize) -> Result<f64> {
+        let seq = self.query(name, start, end)?;
+        Ok(dust_score(&seq))
+    }
+
+    /// Returns the maximal runs of `N` bases (assembly gaps) within an interval.
+    ///
+    /// Each run is a 0-based half-open `(start, end)` interval in contig coordinates and
+    /// runs are returned in ascending order. Both `N` and `n` are considered gap bases.
+    /// Runs are clipped to the queried interval.
+    ///
+    /// The interval is read directly from the memory map, skipping line terminators,
+    /// so no buffer is allocated for uncompressed files.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/masked.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/masked.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr2 is acgtacgtac|NNNNNNNNNN|ACGTA
+    /// let runs = faidx.n_runs("chr2", 0, 25).unwrap();
+    /// assert_eq!(runs, vec![(10, 20)]);
+    /// ```
+    pub fn n_runs(&self, name: &str, start: usize, end: usize) -> Result<Vec<(usize, usize)>> {
+        let seq_slice = self.fetch_interval(name, start, end)?;
+        let mut runs = Vec::new();
+        let mut run_start = None;
+        let mut pos = start;
+        for &c in seq_slice.iter().filter(|&&c| !is_line_break(c)) {
+            match (c == b'N' || c == b'n', run_start) {
+                (true, None) => run_start = Some(pos),
+                (false, Some(run)) => {
+                    runs.push((run, pos));
+                    run_start = None;
+                }
+                _ => {}
+            }
+            pos += 1;
+        }
+        if let Some(run) = run_start {
+            runs.push((run, pos));
+        }
+        Ok(runs)
+    }
+
+    /// Returns the maximal runs of non-`N` bases of a contig.
+    ///
+    /// Each segment is a 0-based half-open `(start, end)` interval in contig coordinates
+    /// and segments are returned in ascending order. This is the complement of the
+    /// assembly gaps reported by `n_runs` over the whole contig, where both `N` and `n`
+    /// are gap bases. Coordinate offsets are not applied, as in `query_all`, and a contig
+    /// without sequence has no segments.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the sequence cannot be read from the FASTA file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/masked.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/masked.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// // chr2 is acgtacgtac|NNNNNNNNNN|ACGTA
+    /// let segments = faidx.gapless_segments("chr2").unwrap();
+    /// assert_eq!(segments, vec![(0, 10), (20, 25)]);
+    /// ```
+    pub fn gapless_segments(&self, name: &str) -> Result<Vec<(usize, usize)>> {
+        let entry = match self.entry(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        let mut segments = Vec::new();
+        if entry.length == 0 {
+            return Ok(segments);
+        }
+        let query_pos = QueryPosition::new(0, entry.length, entry);
+        let seq_slice = self
+            .storage
+            .fetch_query(&query_pos)
+            .map_err(|e| read_error(name, e))?;
+        let mut segment_start = None;
+        let mut pos = 0;
+        for &c in seq_slice.iter().filter(|&&c| !is_line_break(c)) {
+            match (c == b'N' || c == b'n', segment_start) {
+                (false, None) => segment_start = Some(pos),
+                (true, Some(segment)) => {
+                    segments.push((segment, pos));
+                    segment_start = None;
+                }
+                _ => {}
+            }
+            pos += 1;
+        }
+        if let Some(segment) = segment_start {
+            segments.push((segment, pos));
+        }
+        Ok(segments)
+    }
+
+    /// Returns the number of each IUPAC ambiguity code in an interval.
+    ///
+    /// Only the ambiguity codes `R`, `Y`, `S`, `W`, `K`, `M`, `B`, `D`, `H`, and `V` are
+    /// counted (`N` is not). Bases are case-folded so both `r` and `R` are counted as `R`.
+    /// Codes that do not appear in the interval are not present in the map.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the query `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `query`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use faiquery::{FastaIndex, IndexedFasta};
+    ///
+    /// let index = FastaIndex::from_filepath("example_data/masked.fa.fai")
+    ///    .expect("Could not read index file");
+    /// let faidx = IndexedFasta::new(index, "example_data/masked.fa")
+    ///     .expect("Could not read FASTA file");
+    ///
+    /// let counts = faidx.ambiguity_counts("chr1", 20, 40).unwrap();
+    /// assert_eq!(counts.len(), 4);
+    /// assert_eq!(counts[&b'R'], 1);
+    /// assert!(!counts.contains_key(&b'N'));
+    /// ```
+    pub fn ambiguity_counts(
+        &self,
+        name: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<HashMap<u8, usize>> {
+        let seq_slice = self.fetch_interval(name, start, end)?;
+        let mut counts = HashMap::new();
+        for c in seq_slice.iter().map(u8::to_ascii_uppercase) {
+            if matches!(
+                c,
+                b'R' | b'Y' | b'S' | b'W' | b'K' | b'M' | b'B' | b'D' | b'H' | b'V'
+            ) {
+                *counts.entry(c).or_insert(0) += 1;
+            }
+        }
+        Ok(counts)
+    }
+}
+
+/// Copies a raw sequence without its line terminators.
+fn strip_newlines(seq: &[u8]) -> Vec<u8> {
+    let mut stripped = Vec::with_capacity(seq.len());
+    extend_stripped(seq, &mut stripped);
+    stripped
+}
+
+/// Adds the contig name to an error reading its bytes from the storage.
+fn read_error(name: &str, err: anyhow::Error) -> anyhow::Error {
+    anyhow!("Failed to read {} from the FASTA file: {}", name, err)
 }
@@ -0,0 +1,131 @@
+use crate::{bgzf::GziIndex, storage::Storage, FastaIndex, IndexedFasta};
+use anyhow::Result;
+use memmap2::Mmap;
+use std::{fs::File, sync::Mutex};
+
+/// The backing storage used by an `IndexedFasta` to read the FASTA file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Memory-map the plain-text FASTA file (see `IndexedFasta::new`).
+    ///
+    /// Memory maps are not available on `wasm32`, where the file is instead read
+    /// into memory as with `Backend::InMemory`.
+    #[default]
+    Mmap,
+    /// Read the plain-text FASTA file with `seek` and `read_exact`
+    /// (see `IndexedFasta::new_with_file`).
+    File,
+    /// Memory-map a BGZF-compressed FASTA file with its `.gzi` block index
+    /// (see `IndexedFasta::new_bgzf`).
+    Bgzf {
+        /// The path to the `.gzi` block index.
+        gzi_path: String,
+    },
+    /// Query the contents of a plain-text FASTA file held in memory.
+    ///
+    /// The path given to the builder is not opened. Queries slice the bytes exactly as
+    /// they would a memory map, so the zero-copy `query_buffer` methods are supported.
+    /// This works on platforms without memory maps such as `wasm32`.
+    InMemory(Vec<u8>),
+}
+
+/// A builder to configure and create an `IndexedFasta`.
+///
+/// Created with `IndexedFasta::builder`. The default settings are equivalent to
+/// `IndexedFasta::new`.
+///
+/// # Example
+///
+/// ```
+/// use faiquery::{Backend, FastaIndex, IndexedFasta};
+///
+/// let index = FastaIndex::from_filepath("example_data/masked.fa.fai")
+///     .expect("Could not read index file");
+/// let mut faidx = IndexedFasta::builder(index, "example_data/masked.fa")
+///     .backend(Backend::File)
+///     .buffer_capacity(1024)
+///     .uppercase(true)
+///     .build()
+///     .expect("Could not open FASTA file");
+///
+/// let seq = faidx.query("chr1", 4, 12).unwrap();
+/// assert_eq!(seq, b"ACGTACGT");
+/// ```
+#[derive(Debug)]
+pub struct IndexedFastaBuilder {
+    index: FastaIndex,
+    path: String,
+    backend: Backend,
+    buffer_capacity: usize,
+    uppercase: bool,
+}
+impl IndexedFastaBuilder {
+    pub(crate) fn new(index: FastaIndex, path: &str) -> Self {
+        Self {
+            index,
+            path: path.to_string(),
+            backend: Backend::default(),
+            buffer_capacity: 0,
+            uppercase: false,
+        }
+    }
+
+    /// Sets the backing storage used to read the FASTA file.
+    ///
+    /// Defaults to `Backend::Mmap`.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Preallocates the internal query buffer to hold `capacity` bytes.
+    ///
+    /// Queries clear the buffer but never shrink it, so its capacity only grows
+    /// from this starting point.
+    ///
+    /// Defaults to `0` (the buffer grows with the first queries).
+    pub fn buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity;
+        self
+    }
+
+    /// Uppercases the sequence of all buffered queries as it is read.
+    ///
+    /// This applies to every query that copies the sequence (e.g. `query`,
+    /// `query_into`, and `query_cow`) but not to the zero-copy `query_buffer`
+    /// methods, which return the bytes of the file unchanged.
+    ///
+    /// Defaults to `false`.
+    pub fn uppercase(mut self, uppercase: bool) -> Self {
+        self.uppercase = uppercase;
+        self
+    }
+
+    /// Opens the FASTA file and creates the `IndexedFasta`.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the FASTA file cannot be opened or memory-mapped.
+    /// - Error if the `.gzi` block index of a `Backend::Bgzf` cannot be read.
+    pub fn build(self) -> Result<IndexedFasta> {
+        let storage = match self.backend {
+            #[cfg(not(target_arch = "wasm32"))]
+            Backend::Mmap => Storage::Mmap(unsafe { Mmap::map(&File::open(&self.path)?)? }),
+            #[cfg(target_arch = "wasm32")]
+            Backend::Mmap => Storage::InMemory(std::fs::read(&self.path)?),
+            Backend::File => Storage::File(Mutex::new(File::open(&self.path)?)),
+            Backend::Bgzf { gzi_path } => {
+                let gzi = GziIndex::from_filepath(&gzi_path)?;
+                let map = unsafe { Mmap::map(&File::open(&self.path)?)? };
+                Storage::Bgzf { map, gzi }
+            }
+            Backend::InMemory(data) => Storage::InMemory(data),
+        };
+        Ok(IndexedFasta::from_storage(
+            self.index,
+            storage,
+            Vec::with_capacity(self.buffer_capacity),
+            self.uppercase,
+        ))
+    }
+}
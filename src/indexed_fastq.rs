@@ -0,0 +1,118 @@
+use crate::{
+    query_position::{validate_interval, QueryPosition},
+    storage::Storage,
+    FastqIndex, IndexEntry,
+};
+use anyhow::{anyhow, bail, Result};
+#[cfg(not(target_arch = "wasm32"))]
+use memmap2::Mmap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+
+/// An indexed FASTQ file.
+///
+/// This pairs a `FastqIndex` with the memory-mapped FASTQ file so the sequence and
+/// quality of a region of a read can be queried by name, with the same coordinates
+/// (0-based, half-open) and line handling as `IndexedFasta::query`.
+/// Both queries copy the region into an internal buffer with newlines removed.
+///
+/// # Example
+///
+/// ```
+/// use faiquery::{FastqIndex, IndexedFastq};
+///
+/// let index = FastqIndex::from_filepath("example_data/example.fq.fai")
+///     .expect("Could not read index file");
+/// let mut fastq = IndexedFastq::new(index, "example_data/example.fq")
+///     .expect("Could not read FASTQ file");
+///
+/// let seq = fastq.query_seq("read3", 6, 10).unwrap();
+/// assert_eq!(seq, b"GTAC");
+/// let qual = fastq.query_qual("read3", 6, 10).unwrap();
+/// assert_eq!(qual, b"GHIJ");
+/// ```
+#[derive(Debug)]
+pub struct IndexedFastq {
+    index: FastqIndex,
+    storage: Storage,
+    buffer: Vec<u8>,
+}
+impl IndexedFastq {
+    /// Create a new `IndexedFastq` from a `FastqIndex` and a file path.
+    ///
+    /// # Errors
+    ///
+    /// Error if the FASTQ file cannot be opened or memory-mapped.
+    pub fn new(index: FastqIndex, path: &str) -> Result<Self> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let storage = Storage::Mmap(unsafe { Mmap::map(&File::open(path)?)? });
+        #[cfg(target_arch = "wasm32")]
+        let storage = Storage::InMemory(std::fs::read(path)?);
+        Ok(Self {
+            index,
+            storage,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Query the sequence of a read by name and position.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the read `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `IndexedFasta::query`).
+    /// - Error if the sequence cannot be read from the FASTQ file.
+    pub fn query_seq(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        let entry = match self.index.get(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        fill_buffer(&self.storage, &mut self.buffer, entry, start, end)?;
+        Ok(&self.buffer)
+    }
+
+    /// Query the quality string of a read by name and position.
+    ///
+    /// The quality characters are returned as written in the file (e.g. Phred+33),
+    /// one for each base of `query_seq` over the same interval.
+    ///
+    /// # Errors
+    ///
+    /// - Error if the read `name` is not found in the index.
+    /// - Error if the interval is malformed or out of bounds (see `IndexedFasta::query`).
+    /// - Error if the quality cannot be read from the FASTQ file.
+    pub fn query_qual(&mut self, name: &str, start: usize, end: usize) -> Result<&[u8]> {
+        let entry = match self.index.get_qual(name) {
+            Some(entry) => entry,
+            None => bail!("No entry found for {}", name),
+        };
+        fill_buffer(&self.storage, &mut self.buffer, entry, start, end)?;
+        Ok(&self.buffer)
+    }
+
+    /// Returns the length of a read, or `None` if it is not in the index.
+    pub fn seq_len(&self, name: &str) -> Option<usize> {
+        self.index.get(name).map(|entry| entry.length)
+    }
+
+    /// Returns a reference to the `FastqIndex`.
+    pub fn index(&self) -> &FastqIndex {
+        &self.index
+    }
+}
+
+/// Copy an interval of `entry` into `buffer` without its newline characters.
+fn fill_buffer(
+    storage: &Storage,
+    buffer: &mut Vec<u8>,
+    entry: &IndexEntry,
+    start: usize,
+    end: usize,
+) -> Result<()> {
+    validate_interval(entry, start, end, true)?;
+    let query_pos = QueryPosition::new(start, end, entry);
+    buffer.clear();
+    storage
+        .extend_bases(&query_pos, buffer)
+        .map_err(|e| anyhow!("Failed to read {} from the FASTQ file: {}", entry.name, e))
+}
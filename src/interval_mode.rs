@@ -0,0 +1,33 @@
+use anyhow::{bail, Result};
+
+/// The coordinate convention of a query interval.
+///
+/// All queries are performed on 0-based half-open intervals; other conventions are
+/// normalized to it before dispatching (see `IndexedFasta::query_with_mode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntervalMode {
+    /// 0-based half-open `[start, end)` coordinates as used by BED and this crate.
+    #[default]
+    HalfOpen0,
+    /// 1-based fully-closed `[start, end]` coordinates as used by GFF, VCF, SAM,
+    /// BLAST, and `samtools faidx`.
+    Closed1,
+}
+impl IntervalMode {
+    /// Converts an interval in this convention to a 0-based half-open interval.
+    ///
+    /// # Errors
+    ///
+    /// Error if the `start` position of a `Closed1` interval is `0`.
+    pub fn to_half_open(&self, start: usize, end: usize) -> Result<(usize, usize)> {
+        match self {
+            IntervalMode::HalfOpen0 => Ok((start, end)),
+            IntervalMode::Closed1 => {
+                if start == 0 {
+                    bail!("1-based start position must be at least 1 (found 0)");
+                }
+                Ok((start - 1, end))
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+/// The features of a single contig, sorted by start.
+#[derive(Debug, Clone, Default)]
+struct ContigFeatures {
+    features: Vec<(usize, usize, String)>,
+    max_len: usize,
+}
+
+/// A set of named feature intervals per contig.
+///
+/// Features are half-open `[start, end)` intervals stored per contig in a vector sorted
+/// by start, so the features overlapping a position are found by binary search.
+/// Attach a store to an `IndexedFasta` with `set_features` to query features alongside
+/// sequence with `overlapping_features`.
+///
+/// # Example
+///
+/// ```
+/// use faiquery::IntervalStore;
+///
+/// let mut store = IntervalStore::new();
+/// store.insert("chr1", 10, 20, "exon1");
+/// store.insert("chr1", 0, 100, "gene1");
+/// store.insert("chr1", 30, 40, "exon2");
+///
+/// assert_eq!(store.overlapping("chr1", 15), vec![(0, 100, "gene1"), (10, 20, "exon1")]);
+/// assert_eq!(store.overlapping("chr1", 20), vec![(0, 100, "gene1")]);
+/// assert!(store.overlapping("chr2", 15).is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IntervalStore {
+    contigs: HashMap<String, ContigFeatures>,
+}
+impl IntervalStore {
+    /// Creates an empty `IntervalStore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the feature `id` covering `[start, end)` of contig `name`.
+    ///
+    /// Features are kept sorted by start, so inserting features already sorted by start
+    /// only appends them. Empty features (`start >= end`) never overlap a position.
+    pub fn insert(&mut self, name: &str, start: usize, end: usize, id: &str) {
+        let contig = self.contigs.entry(name.to_string()).or_default();
+        let idx = contig.features.partition_point(|&(s, _, _)| s <= start);
+        contig.features.insert(idx, (start, end, id.to_string()));
+        contig.max_len = contig.max_len.max(end.saturating_sub(start));
+    }
+
+    /// Returns the `(start, end, id)` of every feature of contig `name` containing `pos`.
+    ///
+    /// Features are returned sorted by start. A contig without features returns an
+    /// empty vector.
+    pub fn overlapping(&self, name: &str, pos: usize) -> Vec<(usize, usize, &str)> {
+        let contig = match self.contigs.get(name) {
+            Some(contig) => contig,
+            None => return Vec::new(),
+        };
+        // No feature is longer than `max_len`, so overlapping features start after
+        // `pos - max_len` and no later than `pos`.
+        let lower = (pos + 1).saturating_sub(contig.max_len);
+        let first = contig.features.partition_point(|&(s, _, _)| s < lower);
+        let last = contig.features.partition_point(|&(s, _, _)| s <= pos);
+        contig.features[first..last]
+            .iter()
+            .filter(|(_, end, _)| pos < *end)
+            .map(|(start, end, id)| (*start, *end, id.as_str()))
+            .collect()
+    }
+
+    /// Returns the number of features in the store.
+    pub fn len(&self) -> usize {
+        self.contigs.values().map(|c| c.features.len()).sum()
+    }
+
+    /// Returns `true` if the store has no features.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::IntervalStore;
+
+    #[test]
+    fn overlapping_matches_linear_scan() {
+        let features = [
+            (5, 9, "a"),
+            (0, 3, "b"),
+            (2, 30, "c"),
+            (7, 8, "d"),
+            (12, 12, "empty"),
+            (7, 15, "e"),
+            (20, 25, "f"),
+        ];
+        let mut store = IntervalStore::new();
+        for (start, end, id) in features {
+            store.insert("chr1", start, end, id);
+        }
+        assert_eq!(store.len(), features.len());
+        for pos in 0..35 {
+            let mut expected = features
+                .iter()
+                .filter(|(start, end, _)| *start <= pos && pos < *end)
+                .copied()
+                .collect::<Vec<_>>();
+            let mut found = store.overlapping("chr1", pos);
+            found.sort_by_key(|&(start, _, id)| (start, id));
+            expected.sort_by_key(|&(start, _, id)| (start, id));
+            assert_eq!(found, expected, "position {}", pos);
+        }
+    }
+
+    #[test]
+    fn empty_store() {
+        let store = IntervalStore::new();
+        assert!(store.is_empty());
+        assert!(store.overlapping("chr1", 0).is_empty());
+    }
+}
@@ -0,0 +1,34 @@
+/// Sorts half-open `(start, end)` intervals and merges those that overlap or are adjacent.
+///
+/// The merged intervals are returned sorted by their start position.
+pub(crate) fn merge_intervals(intervals: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut sorted = intervals.to_vec();
+    sorted.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(sorted.len());
+    for (start, end) in sorted {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod testing {
+    use super::merge_intervals;
+
+    #[test]
+    fn merge_overlapping_and_adjacent() {
+        let merged = merge_intervals(&[(50, 60), (0, 10), (5, 20), (20, 25), (30, 40), (32, 35)]);
+        assert_eq!(merged, vec![(0, 25), (30, 40), (50, 60)]);
+    }
+
+    #[test]
+    fn merge_disjoint_and_empty() {
+        assert_eq!(merge_intervals(&[]), vec![]);
+        assert_eq!(merge_intervals(&[(10, 20), (0, 5)]), vec![(0, 5), (10, 20)]);
+        assert_eq!(merge_intervals(&[(0, 10), (10, 10)]), vec![(0, 10)]);
+    }
+}
@@ -119,14 +119,120 @@
 //! let num_newlines = seq.iter().filter(|&&b| b == b'\n').count();
 //! assert_eq!(num_newlines, 1);
 //! ```
+//!
+//! ## Alphabets
+//!
+//! Querying is alphabet-agnostic: the offsets are computed purely from the index
+//! geometry, so `query`, `query_buffer`, and their variants work on any FASTA,
+//! including protein or custom alphabets.
+//!
+//! Methods that are inherently nucleotide-specific (`gc_content`, `base_counts`,
+//! `dust_score`, `ambiguity_counts`) simply count nucleotide codes. The reverse
+//! complement methods `query_revcomp`, `query_stranded` with `Strand::Minus`,
+//! `write_region_revcomp`, and `is_inverted_repeat` return an error if the region
+//! contains a byte that is not a nucleotide or IUPAC ambiguity code. The exceptions are
+//! `query_revcomp_with`, which applies its complement table to any alphabet, and
+//! `Sequence::revcomp_owned`, which leaves bytes outside the table unchanged.
+//!
+//! ## Thread safety
+//!
+//! `IndexedFasta` is `Send + Sync`. Every method that writes the internal buffer
+//! takes `&mut self`, so all of the `&self` methods (e.g. `query_buffer` and
+//! `query_into`) can be called concurrently on a shared `IndexedFasta`, for
+//! example from an `Arc` or a `rayon` parallel iterator.
+//!
+//! Use `query_buffer` to borrow directly from the memory map, or `query_into`
+//! with a per-thread buffer to receive sequences without newlines.
+//!
+//! ```rust
+//! use faiquery::{FastaIndex, IndexedFasta};
+//! use rayon::prelude::*;
+//!
+//! let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+//!     .expect("Could not read index file");
+//! let faidx = IndexedFasta::new(index, "example_data/example.fa")
+//!     .expect("Could not read FASTA file");
+//!
+//! let regions = vec![("chr1", 0, 40), ("chr2", 0, 40), ("chr2", 100, 176)];
+//! let num_bases = regions
+//!     .par_iter()
+//!     .map(|&(name, start, end)| {
+//!         let seq = faidx.query_buffer(name, start, end).unwrap();
+//!         seq.iter().filter(|&&b| b != b'\n').count()
+//!     })
+//!     .sum::<usize>();
+//! assert_eq!(num_bases, 156);
+//! ```
+//!
+//! ## Feature flags
+//!
+//! - `regex`: enables `FastaIndex::names_matching`.
+//! - `serde`: implements `Serialize` and `Deserialize` for `FastaIndex` (`IndexEntry`
+//!   implements them without the feature).
+//! - `noodles`: adds `TryFrom` conversions between `FastaIndex`/`IndexEntry` and
+//!   the `noodles_fasta::fai` index types.
+//! - `rayon`: enables `IndexedFasta::par_contigs` to read whole contigs in parallel.
 
+mod advice;
+mod base_counts;
+mod bed;
+mod bgzf;
+mod checksum;
+mod complement;
+mod composition_opts;
+mod contig_cache;
+mod dust;
 mod fasta_index;
+mod fasta_scanner;
+mod fastq_index;
 mod index_entry;
 mod indexed_fasta;
+mod indexed_fasta_builder;
+mod indexed_fastq;
+mod interval_mode;
+mod interval_store;
+mod intervals;
+mod line_ending;
+#[cfg(feature = "noodles")]
+mod noodles;
+mod query_position;
+mod record_cursor;
+mod region;
+mod region_check;
+mod sequence;
+mod storage;
+mod strand;
+mod synthetic;
+mod translate;
+mod window_cursor;
+
+/// The `BaseCounts` struct holds the base composition of a region.
+pub use base_counts::BaseCounts;
+
+/// The `Advice` enum describes the expected access pattern of an `IndexedFasta`.
+pub use advice::Advice;
+
+/// The `ChecksumAlgo` enum selects the digest computed by `IndexedFasta::checksum`.
+pub use checksum::ChecksumAlgo;
+
+/// The `CompositionOpts` struct configures the composition methods of an `IndexedFasta`.
+pub use composition_opts::CompositionOpts;
+
+/// The `DNA_COMPLEMENT` table maps each byte to its DNA complement.
+pub use complement::DNA_COMPLEMENT;
+
+/// The `RNA_COMPLEMENT` table maps each byte to its RNA complement.
+pub use complement::RNA_COMPLEMENT;
+
+/// The `IUPAC_COMPLEMENT` table maps each byte to its IUPAC ambiguity complement.
+pub use complement::IUPAC_COMPLEMENT;
 
 /// The `FastaIndex` struct represents a FAI index file.
 pub use fasta_index::FastaIndex;
 
+/// The `FastqIndex` struct represents a FAI index file of a FASTQ file.
+pub use fastq_index::FastqIndex;
+
 /// The `IndexEntry` struct represents a single entry in a FAI index file.
 pub use index_entry::IndexEntry;
 
@@ -134,13 +240,71 @@ pub use index_entry::IndexEntry;
 /// using the FAI format.
 pub use indexed_fasta::IndexedFasta;
 
+/// The `IndexedFastq` struct represents a FASTQ file that has been indexed
+/// using the FAI format.
+pub use indexed_fastq::IndexedFastq;
+
+/// The `IndexedFastaBuilder` struct configures the backend and query behavior
+/// of an `IndexedFasta`.
+pub use indexed_fasta_builder::IndexedFastaBuilder;
+
+/// The `Backend` enum selects the storage an `IndexedFasta` reads from.
+pub use indexed_fasta_builder::Backend;
+
+/// The `IntervalStore` struct holds named feature intervals per contig.
+pub use interval_store::IntervalStore;
+
+/// The `LineEnding` enum represents the line terminator used by a FASTA record.
+pub use line_ending::LineEnding;
+
+/// The `IntervalMode` enum selects the coordinate convention of a query interval.
+pub use interval_mode::IntervalMode;
+
+/// The `RecordCursor` struct iterates over the full sequence of every record.
+pub use record_cursor::RecordCursor;
+
+/// The `RegionCheck` enum describes whether a region can be queried.
+pub use region_check::RegionCheck;
+
+/// The `Sequence` struct wraps the bytes returned by a query.
+pub use sequence::Sequence;
+
+/// The `Strand` enum selects the strand of a stranded query.
+pub use strand::Strand;
+
+/// The `WindowCursor` struct iterates over fixed-size windows of a contig.
+pub use window_cursor::WindowCursor;
+
+/// The `write_synthetic_fasta` function generates an indexed FASTA for tests and benchmarks.
+pub use synthetic::write_synthetic_fasta;
+
 #[cfg(test)]
 mod testing {
-    use crate::{FastaIndex, IndexedFasta};
+    use crate::{
+        Advice, Backend, ChecksumAlgo, CompositionOpts, FastaIndex, FastqIndex, IndexedFasta,
+        IndexedFastq, IntervalMode, IntervalStore, LineEnding, RegionCheck, Strand,
+    };
     use anyhow::Result;
+    use std::ops::Bound;
 
     const TEST_FASTA: &str = "example_data/example.fa";
     const TEST_FASTA_INDEX: &str = "example_data/example.fa.fai";
+    const TEST_FASTA_GZ: &str = "example_data/example.fa.gz";
+    const TEST_FASTA_GZI: &str = "example_data/example.fa.gz.gzi";
+    const CRLF_FASTA: &str = "example_data/crlf.fa";
+    const CRLF_FASTA_INDEX: &str = "example_data/crlf.fa.fai";
+    const PROTEIN_FASTA: &str = "example_data/protein.fa";
+    const PROTEIN_FASTA_INDEX: &str = "example_data/protein.fa.fai";
+    const MASKED_FASTA: &str = "example_data/masked.fa";
+    const MASKED_FASTA_INDEX: &str = "example_data/masked.fa.fai";
+    const NO_FINAL_NEWLINE_FASTA: &str = "example_data/no_final_newline.fa";
+    const NO_FINAL_NEWLINE_FASTA_INDEX: &str = "example_data/no_final_newline.fa.fai";
+    const EMPTY_CONTIG_FASTA: &str = "example_data/empty_contig.fa";
+    const EMPTY_CONTIG_FASTA_INDEX: &str = "example_data/empty_contig.fa.fai";
+    const TEST_FASTQ: &str = "example_data/example.fq";
+    const TEST_FASTQ_INDEX: &str = "example_data/example.fq.fai";
+    const GAPPED_FASTA: &str = "example_data/gapped.fa";
+    const GAPPED_FASTA_INDEX: &str = "example_data/gapped.fa.fai";
 
     #[test]
     fn standard_usage() -> Result<()> {
@@ -348,4 +512,1960 @@ mod testing {
         assert!(seq.is_err());
         Ok(())
     }
+
+    #[test]
+    fn query_all() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let seq = faidx.query_all("chr2")?.to_vec();
+        assert_eq!(seq.len(), 176);
+        assert_eq!(seq, faidx.query("chr2", 0, 176)?);
+        Ok(())
+    }
+
+    #[test]
+    fn query_all_buffered() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let seq = faidx.query_buffer_all("chr2")?;
+        assert_eq!(seq, faidx.query_buffer("chr2", 0, 176)?);
+        assert!(faidx.query_buffer_all("chr3").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn inverted_repeat_over_newline() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        // CACGTG is its own reverse complement and spans a newline
+        assert!(faidx.is_inverted_repeat("chr1", (83, 89), (83, 89))?);
+        // TAGCTC spans a newline and GAGCTA does not
+        assert!(faidx.is_inverted_repeat("chr2", (51, 57), (68, 74))?);
+        assert!(faidx.is_inverted_repeat("chr2", (68, 74), (51, 57))?);
+        assert!(!faidx.is_inverted_repeat("chr2", (51, 57), (69, 75))?);
+        Ok(())
+    }
+
+    #[test]
+    fn inverted_repeat_out_of_bounds() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert!(faidx
+            .is_inverted_repeat("chr1", (0, 4), (110, 114))
+            .is_err());
+        assert!(faidx.is_inverted_repeat("chr3", (0, 4), (4, 8)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn preview() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let preview = faidx.preview(30)?;
+        let names = preview
+            .iter()
+            .map(|(n, _, _)| n.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["chr1", "chr2"]);
+        let (_, head, tail) = &preview[1];
+        assert_eq!(head, b"TTTTGATCGATCGGCGGGCGCGCGCGGCCA");
+        assert_eq!(tail, b"AGTCGACTGGGGGCGCGCGCGCAAACCACA");
+        Ok(())
+    }
+
+    #[test]
+    fn preview_clamped() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let preview = faidx.preview(100)?;
+        let (_, head, tail) = &preview[0];
+        assert_eq!(head.len(), 100);
+        assert_eq!(tail.len(), 12);
+        let joined = [head.as_slice(), tail.as_slice()].concat();
+        assert_eq!(joined, faidx.query_all("chr1")?);
+        Ok(())
+    }
+
+    #[test]
+    fn clamp_to_seq_len() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert!(faidx.contains("chr2"));
+        assert!(!faidx.contains("chr3"));
+        let end = faidx.seq_len("chr1").map_or(0, |len| len.min(150));
+        let seq = faidx.query("chr1", 100, end)?;
+        assert_eq!(seq.len(), 12);
+        Ok(())
+    }
+
+    #[test]
+    fn query_halves_over_newline() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let (left, right) = faidx.query_halves("chr1", 20, 30)?;
+        assert_eq!(left, b"AGCTA");
+        assert_eq!(right, b"GCTCA");
+        let (left, right) = faidx.query_halves("chr1", 0, 1)?;
+        assert_eq!(left, b"A");
+        assert!(right.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn line_ending_from_geometry() -> Result<()> {
+        use crate::IndexEntry;
+        let mut index = FastaIndex::new();
+        let entry = |name: &str, line_width| IndexEntry {
+            name: name.to_string(),
+            length: 112,
+            offset: 6,
+            line_bases: 28,
+            line_width,
+        };
+        for (name, line_width) in [("lf", 29), ("crlf", 30), ("none", 28)] {
+            index.insert(entry(name, line_width))?;
+        }
+        // An impossible line terminator width is rejected by the index
+        assert!(index.insert(entry("bad", 31)).is_err());
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert_eq!(faidx.line_ending("lf")?, LineEnding::Lf);
+        assert_eq!(faidx.line_ending("crlf")?, LineEnding::CrLf);
+        assert_eq!(faidx.line_ending("none")?, LineEnding::None);
+        assert!(faidx.line_ending("bad").is_err());
+        assert!(faidx.line_ending("missing").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bgzf_matches_plain() -> Result<()> {
+        let mut plain =
+            IndexedFasta::new(FastaIndex::from_filepath(TEST_FASTA_INDEX)?, TEST_FASTA)?;
+        let mut bgzf = IndexedFasta::new_bgzf(
+            FastaIndex::from_filepath(TEST_FASTA_INDEX)?,
+            TEST_FASTA_GZI,
+            TEST_FASTA_GZ,
+        )?;
+        // the example file is compressed in 64 byte blocks so these span several blocks
+        for (name, start, end) in [
+            ("chr1", 0, 112),
+            ("chr1", 50, 60),
+            ("chr2", 0, 176),
+            ("chr2", 100, 150),
+        ] {
+            let expected = plain.query(name, start, end)?.to_vec();
+            assert_eq!(bgzf.query(name, start, end)?, expected);
+        }
+        let seq = bgzf.query_unbounded("chr2", 170, 200)?;
+        assert_eq!(seq, b"ACCACA");
+        Ok(())
+    }
+
+    #[test]
+    fn bgzf_zero_copy_unsupported() -> Result<()> {
+        let faidx = IndexedFasta::new_bgzf(
+            FastaIndex::from_filepath(TEST_FASTA_INDEX)?,
+            TEST_FASTA_GZI,
+            TEST_FASTA_GZ,
+        )?;
+        assert!(faidx.query_buffer("chr1", 0, 10).is_err());
+        assert!(faidx.is_inverted_repeat("chr1", (83, 89), (83, 89))?);
+        Ok(())
+    }
+
+    #[test]
+    fn uppercased_removes_soft_masking() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        let masked = faidx.query("chr1", 0, 60)?.to_vec();
+        assert!(masked.iter().any(|c| c.is_ascii_lowercase()));
+        let seq = faidx.query_uppercased("chr1", 0, 60)?;
+        assert!(!seq.iter().any(|c| c.is_ascii_lowercase()));
+        assert_eq!(seq, masked.to_ascii_uppercase());
+        assert_eq!(&seq[36..40], b"NNNN");
+        Ok(())
+    }
+
+    #[test]
+    fn coordinate_offset() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 20, 60)?.to_vec();
+        let whole = faidx.query_all("chr2")?.to_vec();
+
+        faidx.set_coordinate_offset("chr2", 15);
+        assert_eq!(faidx.query("chr2", 5, 45)?, expected);
+        let raw = faidx.query_buffer("chr2", 5, 45)?;
+        assert_eq!(raw.iter().filter(|&&c| c != b'\n').count(), 40);
+        assert_eq!(faidx.query_all("chr2")?, whole);
+        assert!(faidx.query("chr2", 0, 170).is_err());
+        assert_eq!(faidx.query_unbounded("chr2", 150, 200)?.len(), 11);
+
+        faidx.set_coordinate_offset("chr2", -20);
+        assert_eq!(faidx.query("chr2", 40, 80)?, expected);
+        assert!(faidx.query("chr2", 10, 80).is_err());
+
+        faidx.set_coordinate_offset("chr2", 0);
+        assert_eq!(faidx.query("chr2", 20, 60)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn queryable_fraction() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        assert_eq!(faidx.queryable_fraction("chr1")?, 28.0 / 60.0);
+        assert_eq!(faidx.queryable_fraction("chr2")?, 0.2);
+        assert!(faidx.queryable_fraction("chr3").is_err());
+
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert_eq!(faidx.queryable_fraction("chr1")?, 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn query_many_over_newlines() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let regions = [("chr1", 20, 40), ("chr2", 50, 90), ("chr1", 0, 112)];
+        let expected = regions
+            .iter()
+            .map(|&(name, start, end)| Ok(faidx.query(name, start, end)?.to_vec()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let ranges = faidx.query_many(&regions)?;
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(faidx.buffer().len(), 20 + 40 + 112);
+        for (range, seq) in ranges.into_iter().zip(expected) {
+            assert_eq!(faidx.buffer()[range], seq);
+        }
+
+        let err = faidx.query_many(&[("chr1", 0, 4), ("chr2", 0, 4), ("chr2", 10, 500)]);
+        assert!(err.unwrap_err().to_string().contains("region 2"));
+        Ok(())
+    }
+
+    #[test]
+    fn gc_content_over_newline() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        // CGCGCGGCCA | GATTCGGGCG (newline after position 28)
+        assert_eq!(faidx.gc_content("chr2", 20, 40)?, 0.8);
+        assert_eq!(faidx.gc_content("chr2", 0, 4)?, 0.0);
+        assert!(faidx.gc_content("chr2", 10, 10).is_err());
+        assert!(faidx.gc_content("chr2", 170, 200).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ambiguity_counts() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        let counts = faidx.ambiguity_counts("chr1", 0, 60)?;
+        assert_eq!(counts.len(), 4);
+        for code in [b'R', b'Y', b'K', b'M'] {
+            assert_eq!(counts[&code], 1);
+        }
+        assert!(faidx.ambiguity_counts("chr2", 0, 25)?.is_empty());
+        assert!(faidx.ambiguity_counts("chr1", 0, 61).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn partition_balanced() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+
+        let partitions = faidx.partition(3);
+        assert_eq!(partitions.len(), 3);
+        assert_eq!(partitions[0], vec![("chr1".to_string(), 0, 96)]);
+        assert_eq!(
+            partitions[1],
+            vec![("chr1".to_string(), 96, 112), ("chr2".to_string(), 0, 80)]
+        );
+        assert_eq!(partitions[2], vec![("chr2".to_string(), 80, 176)]);
+
+        for n in 1..300 {
+            let partitions = faidx.partition(n);
+            assert_eq!(partitions.len(), n);
+            let total = partitions
+                .iter()
+                .flatten()
+                .map(|(_, start, end)| end - start)
+                .sum::<usize>();
+            assert_eq!(total, 288);
+        }
+        assert!(faidx.partition(0).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn base_counts_sum_to_length() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        let counts = faidx.base_counts("chr1", 10, 50)?;
+        assert_eq!(counts.total(), 40);
+
+        let counts = faidx.base_counts("chr1", 0, 60)?;
+        assert_eq!(counts.total(), 60);
+        assert_eq!(counts.n, 12);
+        assert_eq!(counts.other, 4);
+        assert_eq!(counts.a, 11);
+        Ok(())
+    }
+
+    #[test]
+    fn query_any_fallback() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 20, 60)?.to_vec();
+        assert_eq!(faidx.query_any(&["2", "chr2", "chr1"], 20, 60)?, expected);
+
+        // A name that exists but fails validation falls through to the next
+        let expected = faidx.query("chr2", 150, 170)?.to_vec();
+        assert_eq!(faidx.query_any(&["chr1", "chr2"], 150, 170)?, expected);
+
+        let err = faidx.query_any(&["2", "chr1"], 150, 170).unwrap_err();
+        assert!(err.to_string().contains("chr1"));
+        assert!(faidx.query_any(&[], 0, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn windows_truncate_last() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let whole = faidx.query_all("chr2")?.to_vec();
+
+        // Non-overlapping windows reconstruct the contig
+        let mut cursor = faidx.windows("chr2", 50, 50)?;
+        let mut joined = Vec::new();
+        let mut lengths = Vec::new();
+        while let Some(window) = cursor.next_window(&mut faidx) {
+            let (_, seq) = window?;
+            lengths.push(seq.len());
+            joined.extend_from_slice(seq);
+        }
+        assert_eq!(lengths, vec![50, 50, 50, 26]);
+        assert_eq!(joined, whole);
+
+        // Overlapping windows stop once the contig end is reached
+        let mut cursor = faidx.windows("chr2", 100, 40)?;
+        let mut intervals = Vec::new();
+        while let Some(window) = cursor.next_window(&mut faidx) {
+            let (start, seq) = window?;
+            assert_eq!(seq, &whole[start..start + seq.len()]);
+            intervals.push((start, start + seq.len()));
+        }
+        assert_eq!(intervals, vec![(0, 100), (40, 140), (80, 176)]);
+
+        assert!(faidx.windows("chr2", 0, 10).is_err());
+        assert!(faidx.windows("chr2", 10, 0).is_err());
+        assert!(faidx.windows("chr3", 10, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_1based_matches_0based() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 20, 60)?.to_vec();
+        assert_eq!(faidx.query_1based("chr2", 21, 60)?, expected);
+
+        let expected = faidx.query_all("chr2")?.to_vec();
+        assert_eq!(faidx.query_1based("chr2", 1, 176)?, expected);
+        assert!(faidx.query_1based("chr2", 1, 177).is_err());
+        assert!(faidx.query_1based("chr2", 0, 10).is_err());
+        assert!(faidx.query_1based("chr2", 11, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn write_region_revcomp_over_newlines() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let forward = faidx.query("chr2", 10, 170)?.to_vec();
+        let expected = forward
+            .iter()
+            .rev()
+            .map(|&c| match c {
+                b'A' => b'T',
+                b'C' => b'G',
+                b'G' => b'C',
+                b'T' => b'A',
+                c => c,
+            })
+            .collect::<Vec<_>>();
+
+        let mut out = Vec::new();
+        faidx.write_region_revcomp("chr2", 10, 170, &mut out)?;
+        assert_eq!(out, expected);
+        assert!(faidx
+            .write_region_revcomp("chr2", 10, 177, &mut Vec::new())
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn check_region() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert_eq!(
+            faidx.check_region("chr1", 0, 112),
+            RegionCheck::Valid { length: 112 }
+        );
+        assert_eq!(
+            faidx.check_region("chr1", 100, 500),
+            RegionCheck::OutOfBounds { length: 112 }
+        );
+        assert_eq!(
+            faidx.check_region("chr1", 112, 113),
+            RegionCheck::OutOfBounds { length: 112 }
+        );
+        assert_eq!(
+            faidx.check_region("chr1", 10, 10),
+            RegionCheck::EmptyInterval { length: 112 }
+        );
+        assert_eq!(
+            faidx.check_region("chr3", 0, 10),
+            RegionCheck::MissingContig
+        );
+        assert_eq!(faidx.check_region("chr3", 0, 10).contig_length(), None);
+
+        // The coordinate offset is applied as in query
+        faidx.set_coordinate_offset("chr1", 10);
+        assert_eq!(
+            faidx.check_region("chr1", 100, 112),
+            RegionCheck::OutOfBounds { length: 112 }
+        );
+        faidx.set_coordinate_offset("chr1", -10);
+        assert_eq!(
+            faidx.check_region("chr1", 5, 15),
+            RegionCheck::OutOfBounds { length: 112 }
+        );
+        assert_eq!(
+            faidx.check_region("chr1", 112, 122),
+            RegionCheck::Valid { length: 112 }
+        );
+        faidx.set_coordinate_offset("chr1", 0);
+
+        // Agrees with query_buffer for every interval
+        for start in 0..120 {
+            for end in 0..120 {
+                let check = faidx.check_region("chr1", start, end);
+                assert_eq!(
+                    check.is_valid(),
+                    faidx.query_buffer("chr1", start, end).is_ok()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn query_into_matches_query() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let mut out = b"stale".to_vec();
+        for (start, end) in [(0, 10), (20, 60), (27, 29), (0, 176)] {
+            let expected = faidx.query("chr2", start, end)?.to_vec();
+            faidx.query_into("chr2", start, end, &mut out)?;
+            assert_eq!(out, expected);
+        }
+        assert!(faidx.query_into("chr2", 0, 177, &mut out).is_err());
+        assert!(faidx.query_into("chr3", 0, 10, &mut out).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn indexed_fasta_is_send_sync() -> Result<()> {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<IndexedFasta>();
+        assert_send_sync::<FastaIndex>();
+
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = std::sync::Arc::new(IndexedFasta::new(index, TEST_FASTA)?);
+        let handles = (0..4)
+            .map(|i| {
+                let faidx = faidx.clone();
+                std::thread::spawn(move || {
+                    let mut out = Vec::new();
+                    faidx.query_into("chr2", i * 10, i * 10 + 40, &mut out)?;
+                    Ok(out)
+                })
+            })
+            .collect::<Vec<_>>();
+        for (i, handle) in handles.into_iter().enumerate() {
+            let out: Result<Vec<u8>> = handle.join().expect("thread panicked");
+            let mut expected = Vec::new();
+            faidx.query_into("chr2", i * 10, i * 10 + 40, &mut expected)?;
+            assert_eq!(out?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn checksum_whole_contig() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert_eq!(
+            faidx.checksum("chr1", 0, 112, ChecksumAlgo::Md5)?,
+            "b7527ac276543d14e98f6e41bdce3b51"
+        );
+        assert_eq!(
+            faidx.checksum("chr1", 0, 112, ChecksumAlgo::Sha512t24)?,
+            "FVKZ6BTkpPIG5TujDau_cy1HMUcxX0Yw"
+        );
+        assert!(faidx.checksum("chr1", 0, 113, ChecksumAlgo::Md5).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn checksum_ignores_soft_masking() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        assert_eq!(
+            faidx.checksum("chr1", 0, 60, ChecksumAlgo::Md5)?,
+            "b5bae009e3775d43c2689fc4d7f13d72"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dust_score_low_complexity() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        // AAAAAAA: 5 AAA triplets => 10 / 4
+        assert_eq!(faidx.dust_score("chr2", 112, 119)?, 2.5);
+        // TATATAT: TAT x3, ATA x2 => (3 + 1) / 4
+        assert_eq!(faidx.dust_score("chr2", 123, 130)?, 1.0);
+        // GCGCGCGCGC repeats score higher than mixed sequence
+        let repeat = faidx.dust_score("chr1", 96, 112)?;
+        let mixed = faidx.dust_score("chr1", 0, 16)?;
+        assert!(repeat > mixed);
+        assert_eq!(faidx.dust_score("chr2", 0, 3)?, 0.0);
+        assert!(faidx.dust_score("chr2", 0, 177).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn validate_index() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        IndexedFasta::new(index, TEST_FASTA)?.validate()?;
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        IndexedFasta::new(index, MASKED_FASTA)?.validate()?;
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        IndexedFasta::new_bgzf(index, TEST_FASTA_GZI, TEST_FASTA_GZ)?.validate()?;
+
+        let corrupt = [
+            // Offset does not follow the header
+            "chr1\t112\t7\t28\t29\nchr2\t176\t128\t28\t29\n",
+            // Sequence is longer than the declared length
+            "chr1\t111\t6\t28\t29\nchr2\t176\t128\t28\t29\n",
+            // Sequence runs into the next header
+            "chr1\t113\t6\t28\t29\nchr2\t176\t128\t28\t29\n",
+            // First line is longer than the declared line bases
+            "chr1\t112\t6\t27\t28\nchr2\t176\t128\t28\t29\n",
+            // Last contig runs past the end of the file
+            "chr1\t112\t6\t28\t29\nchr2\t200\t128\t28\t29\n",
+            // Unindexed trailing record
+            "chr1\t112\t6\t28\t29\n",
+        ];
+        for fai in corrupt {
+            let index = FastaIndex::from_reader(fai.as_bytes())?;
+            let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+            let err = faidx.validate().unwrap_err();
+            assert!(err.to_string().contains("chr"), "{}", err);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn dual_coords() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr1", 20, 60)?.to_vec();
+        let (seq, start, end, genome_start, genome_end) =
+            faidx.query_dual_coords("chr1", 20, 60)?;
+        assert_eq!(seq, expected);
+        assert_eq!((start, end, genome_start, genome_end), (20, 60, 20, 60));
+
+        let (seq, start, end, genome_start, genome_end) =
+            faidx.query_dual_coords("chr2", 170, 176)?;
+        assert_eq!(seq, b"ACCACA");
+        assert_eq!((start, end, genome_start, genome_end), (170, 176, 282, 288));
+
+        // Aliases are resolved before locating the contig in the genome
+        faidx.set_aliases([("2".to_string(), "chr2".to_string())]);
+        let (_, _, _, genome_start, genome_end) = faidx.query_dual_coords("2", 0, 10)?;
+        assert_eq!((genome_start, genome_end), (112, 122));
+
+        faidx.set_coordinate_offset("chr2", 10);
+        let (_, start, end, genome_start, genome_end) = faidx.query_dual_coords("chr2", 0, 10)?;
+        assert_eq!((start, end, genome_start, genome_end), (10, 20, 122, 132));
+        assert!(faidx.query_dual_coords("chr2", 170, 176).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_from_to_end() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let whole = faidx.query_all("chr1")?.to_vec();
+        assert_eq!(faidx.query_from("chr1", 0)?, whole);
+        assert_eq!(faidx.query_from("chr1", 50)?, &whole[50..]);
+        assert_eq!(faidx.query_from("chr1", 111)?, &whole[111..]);
+        assert!(faidx.query_from("chr1", 112).is_err());
+        assert!(faidx.query_from("chr3", 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_cow_matches_query() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        for (start, end) in [(0, 28), (27, 29), (28, 56), (0, 176), (150, 176)] {
+            let expected = faidx.query("chr2", start, end)?.to_vec();
+            let seq = faidx.query_cow("chr2", start, end)?;
+            assert_eq!(&*seq, expected.as_slice());
+            let borrowed = matches!(seq, std::borrow::Cow::Borrowed(_));
+            assert_eq!(borrowed, start / 28 == (end - 1) / 28);
+        }
+        assert!(faidx.query_cow("chr2", 0, 177).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_matches_query_buffer() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let (offset, byte_len) = index.resolve("chr2", 20, 60)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let raw = faidx.query_buffer("chr2", 20, 60)?;
+
+        let file = std::fs::read(TEST_FASTA)?;
+        assert_eq!(&file[offset..offset + byte_len], raw);
+        Ok(())
+    }
+
+    #[test]
+    fn file_backend_matches_mmap() -> Result<()> {
+        let mut mmap = IndexedFasta::new(FastaIndex::from_filepath(TEST_FASTA_INDEX)?, TEST_FASTA)?;
+        let mut file =
+            IndexedFasta::new_with_file(FastaIndex::from_filepath(TEST_FASTA_INDEX)?, TEST_FASTA)?;
+        for (name, start, end) in [("chr1", 0, 112), ("chr2", 20, 60), ("chr2", 170, 176)] {
+            let expected = mmap.query(name, start, end)?.to_vec();
+            assert_eq!(file.query(name, start, end)?, expected);
+            assert_eq!(file.query_cow(name, start, end)?, expected);
+        }
+        let expected = mmap.query_unbounded("chr2", 170, 200)?.to_vec();
+        assert_eq!(file.query_unbounded("chr2", 170, 200)?, expected);
+        assert!(file.is_inverted_repeat("chr1", (83, 89), (83, 89))?);
+        assert!(file.query_buffer("chr1", 0, 10).is_err());
+        file.validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn crlf_matches_lf() -> Result<()> {
+        let mut lf = IndexedFasta::new(FastaIndex::from_filepath(TEST_FASTA_INDEX)?, TEST_FASTA)?;
+        let mut crlf = IndexedFasta::new(FastaIndex::from_filepath(CRLF_FASTA_INDEX)?, CRLF_FASTA)?;
+        assert_eq!(crlf.line_ending("chr1")?, LineEnding::CrLf);
+        for name in ["chr1", "chr2"] {
+            let length = lf.seq_len(name).unwrap();
+            for start in 0..length {
+                for end in (start + 1..=length).step_by(7) {
+                    let expected = lf.query(name, start, end)?.to_vec();
+                    assert_eq!(crlf.query(name, start, end)?, expected);
+                    assert_eq!(crlf.query_cow(name, start, end)?, expected);
+                }
+            }
+            let expected = lf.query_all(name)?.to_vec();
+            assert_eq!(crlf.query_all(name)?, expected);
+        }
+        let expected = lf.query_unbounded("chr2", 170, 200)?.to_vec();
+        assert_eq!(crlf.query_unbounded("chr2", 170, 200)?, expected);
+        crlf.validate()?;
+        Ok(())
+    }
+
+    #[test]
+    fn crlf_query_buffer() -> Result<()> {
+        let index = FastaIndex::from_filepath(CRLF_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, CRLF_FASTA)?;
+        let seq = faidx.query_buffer("chr1", 20, 40)?;
+        assert_eq!(seq.len(), 22);
+        assert_eq!(&seq[8..10], b"\r\n");
+        assert_eq!(faidx.gc_content("chr1", 20, 40)?, 0.5);
+        assert!(faidx.is_inverted_repeat("chr1", (83, 89), (83, 89))?);
+
+        let mut out = Vec::new();
+        faidx.write_region_revcomp("chr1", 0, 112, &mut out)?;
+        assert!(!out.iter().any(|&c| c == b'\r' || c == b'\n'));
+        assert_eq!(out.len(), 112);
+        Ok(())
+    }
+
+    #[test]
+    fn records_in_file_order() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        assert_eq!(faidx.record_names_sorted(), vec!["chr1", "chr2"]);
+
+        let mut cursor = faidx.records();
+        assert_eq!(cursor.remaining(), 2);
+        let mut records = Vec::new();
+        while let Some(record) = cursor.next_record(&mut faidx) {
+            let (name, seq) = record?;
+            records.push((name.to_string(), seq.len()));
+        }
+        assert_eq!(
+            records,
+            vec![("chr1".to_string(), 60), ("chr2".to_string(), 25)]
+        );
+        assert_eq!(cursor.remaining(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn n_runs_over_newlines() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        // The run at 16..24 spans the first newline and 36..40 is lowercase
+        assert_eq!(faidx.n_runs("chr1", 0, 60)?, vec![(16, 24), (36, 40)]);
+        assert_eq!(faidx.n_runs("chr1", 18, 38)?, vec![(18, 24), (36, 38)]);
+        assert!(faidx.n_runs("chr1", 0, 16)?.is_empty());
+        assert!(faidx.n_runs("chr1", 0, 61).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn protein_fasta() -> Result<()> {
+        let index = FastaIndex::from_filepath(PROTEIN_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, PROTEIN_FASTA)?;
+        assert_eq!(faidx.seq_len("sp|P0001|PROT1"), Some(138));
+
+        let seq = faidx.query("sp|P0001|PROT1", 0, 10)?;
+        assert_eq!(seq, b"MKTAYIAKQR");
+        let seq = faidx.query("sp|P0001|PROT1", 55, 65)?;
+        assert_eq!(seq, b"QVKVKALPDA");
+        let seq = faidx.query_all("sp|P0002|PROT2")?;
+        assert_eq!(seq.len(), 192);
+        assert_eq!(&seq[185..], b"TVPASA*");
+        let raw = faidx.query_buffer("sp|P0002|PROT2", 55, 65)?;
+        assert_eq!(raw, b"SQPRG\nRRQPI");
+        faidx.validate()?;
+
+        // Nucleotide-only methods are guarded
+        let mut out = Vec::new();
+        assert!(faidx
+            .write_region_revcomp("sp|P0001|PROT1", 0, 10, &mut out)
+            .is_err());
+        assert!(faidx
+            .is_inverted_repeat("sp|P0001|PROT1", (0, 5), (5, 10))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn write_region_rewraps() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 10, 110)?.to_vec();
+
+        let mut out = Vec::new();
+        faidx.write_region("chr2", 10, 110, 30, "region", &mut out)?;
+        let text = String::from_utf8(out)?;
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], ">region");
+        assert_eq!(
+            lines[1..].iter().map(|l| l.len()).collect::<Vec<_>>(),
+            vec![30, 30, 30, 10]
+        );
+        assert_eq!(lines[1..].concat().as_bytes(), expected.as_slice());
+
+        // The output is a valid FASTA record
+        let entries = crate::fasta_scanner::scan_fasta(text.as_bytes())?;
+        assert_eq!(entries[0].length, 100);
+        assert_eq!(entries[0].line_bases, 30);
+
+        assert!(faidx
+            .write_region("chr2", 10, 110, 0, "region", &mut Vec::new())
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn builder_backends() -> Result<()> {
+        let gzi_path = TEST_FASTA_GZI.to_string();
+        let mut mmap =
+            IndexedFasta::builder(FastaIndex::from_filepath(TEST_FASTA_INDEX)?, TEST_FASTA)
+                .build()?;
+        let mut file =
+            IndexedFasta::builder(FastaIndex::from_filepath(TEST_FASTA_INDEX)?, TEST_FASTA)
+                .backend(Backend::File)
+                .build()?;
+        let mut bgzf =
+            IndexedFasta::builder(FastaIndex::from_filepath(TEST_FASTA_INDEX)?, TEST_FASTA_GZ)
+                .backend(Backend::Bgzf { gzi_path })
+                .buffer_capacity(256)
+                .build()?;
+        let expected = mmap.query("chr2", 20, 150)?.to_vec();
+        assert_eq!(file.query("chr2", 20, 150)?, expected.as_slice());
+        assert_eq!(bgzf.query("chr2", 20, 150)?, expected.as_slice());
+        assert!(mmap.query_buffer("chr2", 20, 150).is_ok());
+        assert!(file.query_buffer("chr2", 20, 150).is_err());
+
+        let missing =
+            IndexedFasta::builder(FastaIndex::from_filepath(TEST_FASTA_INDEX)?, TEST_FASTA_GZ)
+                .backend(Backend::Bgzf {
+                    gzi_path: "example_data/missing.gzi".to_string(),
+                })
+                .build();
+        assert!(missing.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn builder_uppercase() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::builder(index, MASKED_FASTA)
+            .uppercase(true)
+            .build()?;
+        assert_eq!(faidx.query("chr1", 4, 12)?, b"ACGTACGT");
+
+        let mut out = Vec::new();
+        faidx.query_into("chr1", 4, 12, &mut out)?;
+        assert_eq!(out, b"ACGTACGT");
+        assert_eq!(&*faidx.query_cow("chr1", 4, 12)?, b"ACGTACGT");
+        assert_eq!(faidx.query_with_newlines("chr1", 4, 12)?.0, b"ACGTACGT");
+
+        // The zero-copy queries return the file bytes unchanged
+        assert_eq!(faidx.query_buffer("chr1", 4, 12)?, b"ACGTacgt");
+        Ok(())
+    }
+
+    #[test]
+    fn query_str() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = String::from_utf8(faidx.query("chr2", 20, 60)?.to_vec())?;
+        assert_eq!(faidx.query_str("chr2", 20, 60)?, expected);
+        assert!(faidx.query_str("chr2", 20, 200).is_err());
+
+        let fasta = std::env::temp_dir().join("faiquery_query_str.fa");
+        std::fs::write(&fasta, b">chr1\nAC\xffGT\n")?;
+        let index = FastaIndex::from_reader("chr1\t5\t6\t5\t6\n".as_bytes())?;
+        let mut faidx = IndexedFasta::new(index, fasta.to_str().unwrap())?;
+        assert!(faidx.query_str("chr1", 0, 5).is_err());
+        assert_eq!(faidx.query_str("chr1", 3, 5)?, "GT");
+        Ok(())
+    }
+
+    #[test]
+    fn query_merged() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let first = faidx.query("chr2", 0, 40)?.to_vec();
+        let second = faidx.query("chr2", 100, 176)?.to_vec();
+
+        let (merged, seqs) =
+            faidx.query_merged("chr2", &[(150, 176), (10, 40), (0, 20), (100, 150)])?;
+        assert_eq!(merged, vec![(0, 40), (100, 176)]);
+        assert_eq!(seqs, vec![first.as_slice(), second.as_slice()]);
+
+        let (merged, seqs) = faidx.query_merged("chr2", &[])?;
+        assert!(merged.is_empty() && seqs.is_empty());
+
+        assert!(faidx.query_merged("chr2", &[(0, 10), (20, 20)]).is_err());
+        assert!(faidx.query_merged("chr2", &[(0, 10), (170, 180)]).is_err());
+        assert!(faidx.query_merged("chr3", &[(0, 10)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_fasta() -> Result<()> {
+        let fasta = std::env::temp_dir().join("faiquery_truncated.fa");
+        std::fs::write(&fasta, &std::fs::read(TEST_FASTA)?[..150])?;
+        let fasta = fasta.to_str().unwrap();
+
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, fasta)?;
+        assert!(faidx.query("chr1", 0, 112).is_ok());
+        assert!(faidx.query("chr2", 0, 10).is_ok());
+        let err = faidx.query("chr2", 0, 100).unwrap_err().to_string();
+        assert!(err.contains("chr2") && err.contains("128..231"));
+        assert!(faidx.query_buffer("chr2", 0, 100).is_err());
+        assert!(faidx.query_unbounded("chr2", 150, 200).is_err());
+        assert!(faidx.query_buffer_unbounded("chr2", 150, 200).is_err());
+        assert!(faidx.query_cow("chr2", 0, 100).is_err());
+        assert!(faidx.query_into("chr2", 0, 100, &mut Vec::new()).is_err());
+
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new_with_file(index, fasta)?;
+        let err = faidx.query("chr2", 0, 100).unwrap_err().to_string();
+        assert!(err.contains("chr2") && err.contains("128..231"));
+        Ok(())
+    }
+
+    #[test]
+    fn query_circular() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let tail = faidx.query("chr2", 126, 176)?.to_vec();
+        let head = faidx.query("chr2", 0, 50)?.to_vec();
+        let inner = faidx.query("chr2", 20, 120)?.to_vec();
+
+        // Wrapping across the end of the contig
+        let seq = faidx.query_circular("chr2", 126, 226)?;
+        assert_eq!(seq, [tail, head].concat().as_slice());
+
+        // Not wrapping
+        let seq = faidx.query_circular("chr2", 20, 120)?;
+        assert_eq!(seq, inner.as_slice());
+
+        // The full circle from any start
+        assert_eq!(faidx.query_circular("chr2", 100, 276)?.len(), 176);
+        assert!(faidx.query_circular("chr2", 100, 277).is_err());
+        assert!(faidx.query_circular("chr2", 176, 180).is_err());
+        assert!(faidx.query_circular("chr2", 10, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_with_mode() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 9, 40)?.to_vec();
+        assert_eq!(
+            faidx.query_with_mode("chr2", 9, 40, IntervalMode::HalfOpen0)?,
+            expected.as_slice()
+        );
+        assert_eq!(
+            faidx.query_with_mode("chr2", 10, 40, IntervalMode::Closed1)?,
+            expected.as_slice()
+        );
+        assert_eq!(
+            faidx.query_with_mode("chr2", 176, 176, IntervalMode::Closed1)?,
+            b"A"
+        );
+        assert!(faidx
+            .query_with_mode("chr2", 0, 10, IntervalMode::Closed1)
+            .is_err());
+        assert!(faidx
+            .query_with_mode("chr2", 170, 177, IntervalMode::Closed1)
+            .is_err());
+        assert_eq!(IntervalMode::default(), IntervalMode::HalfOpen0);
+        Ok(())
+    }
+
+    #[test]
+    fn advise() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        for advice in [
+            Advice::Sequential,
+            Advice::Random,
+            Advice::WillNeed,
+            Advice::Normal,
+        ] {
+            faidx.advise(advice)?;
+            assert_eq!(faidx.query("chr1", 0, 10)?, b"ACCTACGATC");
+        }
+
+        // A no-op on files that are not memory-mapped
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new_with_file(index, TEST_FASTA)?;
+        faidx.advise(Advice::Sequential)?;
+        Ok(())
+    }
+
+    #[test]
+    fn prefetch() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        faidx.prefetch("chr1", 0, 112)?;
+        faidx.prefetch("chr2", 50, 60)?;
+        assert!(faidx.prefetch("chr2", 60, 50).is_err());
+        assert!(faidx.prefetch("chr3", 0, 10).is_err());
+
+        // A no-op on BGZF-compressed and non-mmapped files
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new_bgzf(index, TEST_FASTA_GZI, TEST_FASTA_GZ)?;
+        faidx.prefetch("chr2", 50, 60)?;
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new_with_file(index, TEST_FASTA)?;
+        faidx.prefetch("chr2", 50, 60)?;
+        Ok(())
+    }
+
+    #[test]
+    fn region_lengths() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        for (start, end) in [(0, 10), (20, 40), (0, 176), (27, 29), (100, 150)] {
+            let raw = faidx.query_buffer("chr2", start, end)?.len();
+            assert_eq!(faidx.raw_region_len("chr2", start, end)?, raw);
+            let len = faidx.query("chr2", start, end)?.len();
+            assert_eq!(faidx.region_len("chr2", start, end)?, len);
+        }
+        assert!(faidx.region_len("chr2", 10, 10).is_err());
+        assert!(faidx.raw_region_len("chr3", 0, 10).is_err());
+
+        let index = FastaIndex::from_filepath(CRLF_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, CRLF_FASTA)?;
+        assert_eq!(faidx.region_len("chr1", 20, 40)?, 20);
+        assert_eq!(faidx.raw_region_len("chr1", 20, 40)?, 22);
+        Ok(())
+    }
+
+    #[test]
+    fn geometry_copy_matches_scanning() -> Result<()> {
+        // A small linear congruential generator for reproducible random intervals
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move |bound: usize| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 33) as usize % bound
+        };
+        for (fasta, fai) in [
+            (TEST_FASTA, TEST_FASTA_INDEX),
+            (CRLF_FASTA, CRLF_FASTA_INDEX),
+            (PROTEIN_FASTA, PROTEIN_FASTA_INDEX),
+        ] {
+            let index = FastaIndex::from_filepath(fai)?;
+            let names = (0..index.len())
+                .map(|i| index.get_nth(i).unwrap().name.clone())
+                .collect::<Vec<_>>();
+            let mut faidx = IndexedFasta::new(index, fasta)?;
+            for _ in 0..500 {
+                let name = &names[next(names.len())];
+                let length = faidx.seq_len(name).unwrap();
+                let start = next(length);
+                let end = start + 1 + next(length - start);
+                let scanned = faidx
+                    .query_buffer(name, start, end)?
+                    .iter()
+                    .copied()
+                    .filter(|&c| c != b'\n' && c != b'\r')
+                    .collect::<Vec<_>>();
+                assert_eq!(faidx.query(name, start, end)?, scanned.as_slice());
+                assert_eq!(scanned.len(), end - start);
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_contigs() -> Result<()> {
+        use rayon::prelude::*;
+
+        let index = FastaIndex::from_filepath(CRLF_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, CRLF_FASTA)?;
+        let contigs = faidx.par_contigs().collect::<Result<Vec<_>>>()?;
+        assert_eq!(contigs.len(), 2);
+        for (name, seq) in contigs {
+            assert_eq!(seq, faidx.query_all(&name)?);
+        }
+
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let faidx = IndexedFasta::builder(index, MASKED_FASTA)
+            .backend(Backend::File)
+            .uppercase(true)
+            .build()?;
+        for contig in faidx
+            .par_contigs_filtered(|entry| entry.name == "chr1")
+            .collect::<Vec<_>>()
+        {
+            let (name, seq) = contig?;
+            assert_eq!(name, "chr1");
+            assert!(!seq.iter().any(u8::is_ascii_lowercase));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn no_final_newline() -> Result<()> {
+        let index = FastaIndex::from_filepath(NO_FINAL_NEWLINE_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, NO_FINAL_NEWLINE_FASTA)?;
+        faidx.validate()?;
+        assert_eq!(faidx.query_all("chr2")?, b"TTTTAAAACCCCGGGGATAT");
+        assert_eq!(faidx.query("chr2", 12, 20)?, b"GGGGATAT");
+        assert_eq!(faidx.query_unbounded("chr2", 15, 40)?, b"GATAT");
+        assert_eq!(
+            faidx.query_buffer("chr2", 0, 20)?,
+            b"TTTTAAAACC\nCCGGGGATAT"
+        );
+        assert_eq!(faidx.query_buffer_all("chr2")?.len(), 21);
+        assert_eq!(&*faidx.query_cow("chr2", 10, 20)?, b"CCGGGGATAT");
+        faidx.prefetch("chr2", 0, 20)?;
+
+        // A contig ending on a full line before another record keeps its terminator
+        assert_eq!(faidx.query_buffer("chr1", 10, 20)?, b"GGCCGGCCGG\n");
+
+        let index = FastaIndex::from_filepath(NO_FINAL_NEWLINE_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new_with_file(index, NO_FINAL_NEWLINE_FASTA)?;
+        assert_eq!(faidx.query_all("chr2")?, b"TTTTAAAACCCCGGGGATAT");
+        Ok(())
+    }
+
+    #[test]
+    fn query_padded() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let inner = faidx.query("chr2", 50, 90)?.to_vec();
+        assert_eq!(faidx.query_padded("chr2", 50, 90, b'N')?, inner.as_slice());
+
+        // Padded on both sides
+        let full = faidx.query_all("chr2")?.to_vec();
+        let seq = faidx.query_padded("chr2", -10, 186, b'N')?;
+        assert_eq!(seq.len(), 196);
+        assert_eq!(&seq[..10], b"NNNNNNNNNN");
+        assert_eq!(&seq[10..186], full.as_slice());
+        assert_eq!(&seq[186..], b"NNNNNNNNNN");
+
+        // Entirely outside the contig
+        assert_eq!(faidx.query_padded("chr2", -20, -15, b'-')?, b"-----");
+        assert_eq!(faidx.query_padded("chr2", 176, 180, b'N')?, b"NNNN");
+        assert_eq!(faidx.query_padded("chr2", 200, 210, b'N')?, b"NNNNNNNNNN");
+        faidx.set_coordinate_offset("chr2", 10);
+        assert_eq!(faidx.query_padded("chr2", 170, 175, b'N')?, b"NNNNN");
+        assert!(faidx.query_padded("chr2", 0, i64::MAX, b'N').is_err());
+        assert!(faidx.query_padded("chr2", i64::MIN, 0, b'N').is_err());
+
+        assert!(faidx.query_padded("chr2", 10, 10, b'N').is_err());
+        assert!(faidx.query_padded("chr3", 0, 10, b'N').is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_signed() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 76, 175)?.to_vec();
+        assert_eq!(faidx.query_signed("chr2", -100, -1)?, expected.as_slice());
+        assert_eq!(faidx.query_signed("chr2", 76, -1)?, expected.as_slice());
+        assert_eq!(faidx.query_signed("chr2", -100, 175)?, expected.as_slice());
+        assert_eq!(faidx.query_signed("chr2", -1, 176)?, b"A");
+        assert_eq!(faidx.query_signed("chr2", -176, 4)?, b"TTTT");
+
+        assert!(faidx.query_signed("chr2", -177, -1).is_err());
+        assert!(faidx.query_signed("chr2", -1, -1).is_err());
+        assert!(faidx.query_signed("chr2", -1, -2).is_err());
+        assert!(faidx.query_signed("chr2", 170, 177).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_translated() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        // chr1:0-12 is ACCTACGATCGA, spanning no newline
+        assert_eq!(faidx.query_translated("chr1", 0, 12, 0)?, b"TYDR");
+        assert_eq!(faidx.query_translated("chr1", 0, 12, 1)?, b"PTI");
+        assert_eq!(faidx.query_translated("chr1", 0, 12, 2)?, b"LRS");
+
+        // Across a line break the codons are formed from the stripped sequence:
+        // AGC TAG CT|C ATC
+        assert_eq!(faidx.query_translated("chr1", 20, 32, 0)?, b"S*LI");
+        // and a partial trailing codon is dropped
+        assert_eq!(faidx.query_translated("chr1", 20, 34, 0)?, b"S*LI");
+
+        assert!(faidx.query_translated("chr1", 0, 12, 3).is_err());
+        assert!(faidx.query_translated("chr1", 0, 2, 1).unwrap().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn query_revcomp_with() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let mut expected = Vec::new();
+        faidx.write_region_revcomp("chr2", 10, 100, &mut expected)?;
+        assert_eq!(faidx.query_revcomp("chr2", 10, 100)?, expected.as_slice());
+        assert_eq!(
+            faidx.query_revcomp_with("chr2", 10, 100, &crate::DNA_COMPLEMENT)?,
+            expected.as_slice()
+        );
+
+        // Every base is reversed and complemented, T has no RNA complement
+        let seq = faidx.query("chr2", 0, 10)?.to_vec();
+        let revcomp = faidx.query_revcomp_with("chr2", 0, 10, &crate::RNA_COMPLEMENT)?;
+        assert_eq!(seq, b"TTTTGATCGA");
+        assert_eq!(revcomp, b"UCGTUCTTTT");
+
+        // Custom tables are applied as given
+        let mut identity = [0u8; 256];
+        for (i, c) in identity.iter_mut().enumerate() {
+            *c = i as u8;
+        }
+        assert_eq!(
+            faidx.query_revcomp_with("chr2", 0, 10, &identity)?,
+            b"AGCTAGTTTT"
+        );
+
+        let index = FastaIndex::from_filepath(PROTEIN_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, PROTEIN_FASTA)?;
+        assert!(faidx.query_revcomp("sp|P0001|PROT1", 0, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_bed() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 20, 60)?.to_vec();
+
+        let bed = "track name=test\nchr1\t0\t10\tfeature\nchr2\t20\t60\n\nchr3\t0\t10\nchr1\tx\t10\nchr1\t100\t112\n";
+        let results = faidx.query_bed(bed.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 5);
+        let (name, start, end, seq) = results[0].as_ref().unwrap();
+        assert_eq!((name.as_str(), *start, *end), ("chr1", 0, 10));
+        assert_eq!(seq, b"ACCTACGATC");
+        assert_eq!(results[1].as_ref().unwrap().3, expected);
+
+        // Errors name the offending line and do not stop the iteration
+        assert!(results[2]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("line 5"));
+        assert!(results[3]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("line 6"));
+        assert_eq!(results[4].as_ref().unwrap().3.len(), 12);
+        Ok(())
+    }
+
+    #[test]
+    fn empty_contig() -> Result<()> {
+        let index = FastaIndex::from_filepath(EMPTY_CONTIG_FASTA_INDEX)?;
+        assert_eq!(index.get_nth(1).unwrap().name, "empty");
+        assert!(index.verify_lengths(EMPTY_CONTIG_FASTA)?.is_empty());
+
+        let mut faidx = IndexedFasta::new(index, EMPTY_CONTIG_FASTA)?;
+        faidx.validate()?;
+        assert_eq!(faidx.seq_len("empty"), Some(0));
+        assert_eq!(faidx.query_all("empty")?, b"");
+        assert_eq!(faidx.query_buffer_all("empty")?, b"");
+        assert!(faidx.query("empty", 0, 1).is_err());
+
+        // Neighbouring contigs are unaffected
+        assert_eq!(faidx.query_all("chr1")?, b"ACGTACGTACGTAC");
+        assert_eq!(faidx.query_all("chr2")?, b"TTTTGGGG");
+
+        let mut records = faidx.records();
+        let mut lengths = Vec::new();
+        while let Some(record) = records.next_record(&mut faidx) {
+            let (name, seq) = record?;
+            lengths.push((name.to_string(), seq.len()));
+        }
+        assert!(lengths.contains(&("empty".to_string(), 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn header() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert_eq!(faidx.header("chr1")?, b">chr1");
+        assert_eq!(faidx.header("chr2")?, b">chr2");
+        assert!(faidx.header("chr3").is_err());
+
+        let index = FastaIndex::from_filepath(PROTEIN_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, PROTEIN_FASTA)?;
+        assert_eq!(
+            faidx.header("sp|P0002|PROT2")?,
+            b">sp|P0002|PROT2 Example protein two"
+        );
+
+        let index = FastaIndex::from_filepath(CRLF_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, CRLF_FASTA)?;
+        assert_eq!(faidx.header("chr2")?, b">chr2");
+
+        // An offset that does not follow a header line
+        let index = FastaIndex::from_reader("chr1\t112\t10\t28\t29\n".as_bytes())?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert!(faidx.header("chr1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_index() -> Result<()> {
+        let index = FastaIndex::default();
+        assert_eq!(index, FastaIndex::new());
+        assert!(index.is_empty());
+
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let err = faidx.validate().unwrap_err();
+        assert_eq!(err.to_string(), "Index is empty but the FASTA file is not");
+        assert_eq!(faidx.seq_len("chr1"), None);
+        let err = faidx.query("chr1", 0, 10).unwrap_err();
+        assert_eq!(err.to_string(), "No entry found for chr1");
+        assert!(faidx.query_all("chr1").is_err());
+        assert!(faidx.query_buffer("chr1", 0, 10).is_err());
+        assert!(faidx.header("chr1").is_err());
+        assert!(faidx.preview(4)?.is_empty());
+        assert!(faidx.partition(2).iter().all(|p| p.is_empty()));
+        assert!(faidx.records().next_record(&mut faidx).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn query_concat_mixed_line_widths() -> Result<()> {
+        // chr1 has 20 bases per line and chr2 has 10
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        let regions = [("chr1", 15, 45), ("chr2", 5, 22), ("chr1", 0, 3)];
+        let expected = regions
+            .iter()
+            .map(|&(name, start, end)| Ok(faidx.query(name, start, end)?.to_vec()))
+            .collect::<Result<Vec<_>>>()?;
+
+        assert_eq!(faidx.query_concat(&regions, None)?, expected.concat());
+        assert_eq!(
+            faidx.query_concat(&regions, Some(b'|'))?,
+            expected.join(&b'|')
+        );
+        assert_eq!(faidx.query_concat(&[], Some(b'|'))?, b"");
+
+        let err = faidx.query_concat(&[("chr1", 0, 4), ("chr2", 20, 30)], None);
+        assert!(err.unwrap_err().to_string().contains("region 1"));
+        Ok(())
+    }
+
+    #[test]
+    fn load_contig() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        let expected = faidx.query("chr1", 15, 45)?.to_vec();
+        let whole = faidx.query_all("chr2")?.to_vec();
+
+        faidx.load_contig("chr1")?;
+        faidx.load_contig("chr1")?;
+        assert!(faidx.is_loaded("chr1"));
+        assert!(!faidx.is_loaded("chr2"));
+        assert_eq!(faidx.query("chr1", 15, 45)?, expected);
+        assert_eq!(faidx.query_unbounded("chr1", 50, 80)?.len(), 10);
+        assert!(faidx.query("chr1", 50, 80).is_err());
+        assert_eq!(
+            faidx.query_concat(&[("chr1", 15, 45), ("chr2", 0, 25)], None)?,
+            [expected.as_slice(), whole.as_slice()].concat()
+        );
+        assert!(faidx.load_contig("chr3").is_err());
+
+        // Methods modifying the buffered sequence see the loaded contig
+        assert_eq!(faidx.query_uppercased("chr1", 4, 12)?, b"ACGTACGT");
+        assert_eq!(faidx.query_any(&["chr3", "chr1"], 4, 12)?, b"ACGTacgt");
+        assert_eq!(faidx.query_revcomp("chr1", 0, 4)?, b"ACGT");
+
+        assert!(faidx.unload_contig("chr1"));
+        assert!(!faidx.unload_contig("chr1"));
+        assert_eq!(faidx.query("chr1", 15, 45)?, expected);
+
+        // Loaded contigs keep the case normalization of the `IndexedFasta`
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::builder(index, MASKED_FASTA)
+            .uppercase(true)
+            .build()?;
+        faidx.load_contig("chr2")?;
+        assert_eq!(faidx.query("chr2", 0, 10)?, b"ACGTACGTAC");
+        Ok(())
+    }
+
+    #[test]
+    fn query_buffer_owned() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query_buffer("chr2", 10, 100)?.to_vec();
+        let owned = faidx.query_buffer_owned("chr2", 10, 100)?;
+        assert_eq!(owned, expected);
+        assert!(faidx.query_buffer_owned("chr2", 10, 200).is_err());
+
+        // The owned bytes can be sent across threads
+        let handle = std::thread::spawn(move || owned.len());
+        assert_eq!(handle.join().unwrap(), expected.len());
+
+        // Also available when zero-copy slicing is not
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::builder(index, TEST_FASTA)
+            .backend(Backend::File)
+            .build()?;
+        assert!(faidx.query_buffer("chr2", 10, 100).is_err());
+        assert_eq!(faidx.query_buffer_owned("chr2", 10, 100)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn query_range_bounds() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 20, 60)?.to_vec();
+        assert_eq!(faidx.query_range("chr2", 20..60)?, expected);
+        assert_eq!(faidx.query_range("chr2", 20..=59)?, expected);
+        assert_eq!(
+            faidx.query_range("chr2", (Bound::Excluded(19), Bound::Excluded(60)))?,
+            expected
+        );
+        let head = faidx.query("chr2", 0, 60)?.to_vec();
+        assert_eq!(faidx.query_range("chr2", ..60)?, head);
+        let tail = faidx.query_from("chr2", 20)?.to_vec();
+        assert_eq!(faidx.query_range("chr2", 20..)?, tail);
+        let whole = faidx.query_all("chr2")?.to_vec();
+        assert_eq!(faidx.query_range("chr2", ..)?, whole);
+
+        assert!(faidx.query_range("chr2", 20..20).is_err());
+        assert!(faidx.query_range("chr2", 170..=176).is_err());
+        assert!(faidx
+            .query_range("chr2", (Bound::Excluded(usize::MAX), Bound::Unbounded))
+            .is_err());
+        assert!(faidx.query_range("chr3", ..).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_with_mask() -> Result<()> {
+        // chr1 is ACGTACGTacgtacgtNNNN|NNNNACGTRYKMacgtnnnn|...
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        let seq = faidx.query_with_mask("chr1", (4, 30), (10, 24))?;
+        assert_eq!(seq, b"acgtacGTACGTNNNNNNNNacgtry");
+
+        // The inner interval may span the whole region
+        let seq = faidx.query_with_mask("chr1", (4, 12), (4, 12))?;
+        assert_eq!(seq, b"ACGTACGT");
+
+        assert!(faidx.query_with_mask("chr1", (4, 30), (2, 10)).is_err());
+        assert!(faidx.query_with_mask("chr1", (4, 30), (10, 31)).is_err());
+        assert!(faidx.query_with_mask("chr1", (4, 30), (10, 10)).is_err());
+        assert!(faidx.query_with_mask("chr1", (50, 70), (55, 60)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn gapless_segments() -> Result<()> {
+        // chr1 is ACGTACGTacgtacgtNNNN|NNNNACGTRYKMacgtnnnn|GGCCAATTggccaattACGT
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        let segments = faidx.gapless_segments("chr1")?;
+        assert_eq!(segments, vec![(0, 16), (24, 36), (40, 60)]);
+
+        // Segments and gaps tile the contig
+        let gaps = faidx.n_runs("chr1", 0, 60)?;
+        let mut tiles = [segments, gaps].concat();
+        tiles.sort();
+        assert!(tiles.windows(2).all(|w| w[0].1 == w[1].0));
+        assert_eq!((tiles[0].0, tiles[tiles.len() - 1].1), (0, 60));
+
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert_eq!(faidx.gapless_segments("chr2")?, vec![(0, 176)]);
+        assert!(faidx.gapless_segments("chr3").is_err());
+
+        let index = FastaIndex::from_filepath(EMPTY_CONTIG_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, EMPTY_CONTIG_FASTA)?;
+        assert!(faidx.gapless_segments("empty")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn remap() -> Result<()> {
+        // A copy of example.fa with the same layout but a different first line
+        let original = std::fs::read(TEST_FASTA)?;
+        let mut replaced = original.clone();
+        replaced[6..34].copy_from_slice(&[b'G'; 28]);
+        let path = std::env::temp_dir().join("faiquery_remap.fa");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, &replaced)?;
+
+        for backend in [Backend::Mmap, Backend::File] {
+            let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+            let mut faidx = IndexedFasta::builder(index, TEST_FASTA)
+                .backend(backend)
+                .build()?;
+            faidx.load_contig("chr1")?;
+            assert_eq!(faidx.query("chr1", 0, 4)?, b"ACCT");
+
+            faidx.remap(path)?;
+            assert!(!faidx.is_loaded("chr1"));
+            assert_eq!(faidx.query("chr1", 0, 4)?, b"GGGG");
+            assert_eq!(faidx.query("chr2", 0, 4)?, b"TTTT");
+
+            // A truncated or missing file keeps the current one
+            assert!(faidx.remap(MASKED_FASTA).is_err());
+            assert!(faidx.remap("example_data/missing.fa").is_err());
+            assert_eq!(faidx.query("chr1", 0, 4)?, b"GGGG");
+        }
+
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new_bgzf(index, TEST_FASTA_GZI, TEST_FASTA_GZ)?;
+        assert!(faidx.remap(TEST_FASTA_GZ).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_with_entry() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let expected_entry = index.get("chr1").unwrap().clone();
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr1", 20, 60)?.to_vec();
+
+        let (entry, seq) = faidx.query_with_entry("chr1", 20, 60)?;
+        assert_eq!(entry, expected_entry);
+        assert_eq!(seq, expected);
+        assert!(faidx.query_with_entry("chr1", 100, 120).is_err());
+        assert!(faidx.query_with_entry("chr3", 0, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn aliases() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let chr1 = faidx.query("chr1", 20, 60)?.to_vec();
+        let chr2 = faidx.query("chr2", 20, 60)?.to_vec();
+
+        faidx.set_aliases([
+            ("1".to_string(), "chr1".to_string()),
+            ("NC_000002.12".to_string(), "chr2".to_string()),
+            // An exact name wins over an alias
+            ("chr2".to_string(), "chr1".to_string()),
+            ("3".to_string(), "chr3".to_string()),
+        ]);
+        assert_eq!(faidx.query("1", 20, 60)?, chr1);
+        assert_eq!(faidx.query("chr1", 20, 60)?, chr1);
+        assert_eq!(faidx.query("NC_000002.12", 20, 60)?, chr2);
+        assert_eq!(faidx.query("chr2", 20, 60)?, chr2);
+        assert_eq!(faidx.query_buffer("1", 0, 4)?, b"ACCT");
+        assert_eq!(faidx.seq_len("NC_000002.12"), Some(176));
+        assert_eq!(faidx.region_len("1", 0, 10)?, 10);
+        assert!(faidx.query("3", 0, 10).is_err());
+
+        // Offsets and loaded contigs are shared between a contig and its aliases
+        faidx.set_coordinate_offset("1", 10);
+        assert_eq!(faidx.query("chr1", 10, 50)?, chr1);
+        faidx.set_coordinate_offset("chr1", 0);
+        faidx.load_contig("1")?;
+        assert!(faidx.is_loaded("chr1"));
+        assert_eq!(faidx.query("1", 20, 60)?, chr1);
+        assert!(faidx.unload_contig("chr1"));
+
+        // Replacing the aliases removes the previous ones
+        faidx.set_aliases(Vec::new());
+        assert!(faidx.query("1", 0, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn gc_track_matches_gc_content() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        // Overlapping, adjacent, and gapped windows
+        for (window, step) in [(30, 7), (28, 28), (10, 25), (200, 50), (1, 1)] {
+            let track = faidx.gc_track("chr2", window, step)?;
+            let mut cursor = faidx.windows("chr2", window, step)?;
+            let mut expected = Vec::new();
+            while let Some((start, end)) = cursor.next_interval() {
+                expected.push(faidx.gc_content("chr2", start, end)?);
+            }
+            assert_eq!(track, expected, "window {} step {}", window, step);
+        }
+        assert!(faidx.gc_track("chr2", 0, 10).is_err());
+        assert!(faidx.gc_track("chr2", 10, 0).is_err());
+        assert!(faidx.gc_track("chr3", 10, 10).is_err());
+
+        let index = FastaIndex::from_filepath(EMPTY_CONTIG_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, EMPTY_CONTIG_FASTA)?;
+        assert!(faidx.gc_track("empty", 10, 10)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_features() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert!(faidx.overlapping_features("chr1", 0)?.is_empty());
+
+        let mut features = IntervalStore::new();
+        features.insert("chr2", 0, 4, "polyT");
+        features.insert("chr2", 112, 124, "polyA");
+        faidx.set_features(features);
+        faidx.set_aliases([("2".to_string(), "chr2".to_string())]);
+        assert_eq!(faidx.overlapping_features("2", 3)?, vec![(0, 4, "polyT")]);
+        assert!(faidx.overlapping_features("chr2", 4)?.is_empty());
+        let (start, end, _) = faidx.overlapping_features("chr2", 120)?[0];
+        assert_eq!(faidx.query("chr2", start, end)?, b"AAAAAAATTTTT");
+        assert!(faidx.overlapping_features("chr3", 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn codons_match_translation() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        // Regions spanning line breaks in every frame
+        for frame in 0..3 {
+            let protein = faidx.query_translated("chr2", 20, 70, frame)?;
+            let codons = faidx.codons("chr2", 20, 70, frame)?.collect::<Vec<_>>();
+            assert_eq!(codons.len(), protein.len());
+            let seq = faidx.query("chr2", 20, 70)?.to_vec();
+            let expected = seq[frame as usize..]
+                .chunks_exact(3)
+                .map(|c| [c[0], c[1], c[2]])
+                .collect::<Vec<_>>();
+            assert_eq!(codons, expected);
+        }
+        assert_eq!(faidx.codons("chr1", 0, 3, 1)?.count(), 0);
+        assert_eq!(faidx.codons("chr1", 0, 1, 2)?.count(), 0);
+        assert!(faidx.codons("chr1", 0, 10, 3).is_err());
+        assert!(faidx.codons("chr1", 0, 200, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn is_valid_region_matches_query() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        faidx.set_coordinate_offset("chr2", -10);
+        for name in ["chr1", "chr2", "chr3"] {
+            for start in (0..200).step_by(7) {
+                for end in (0..200).step_by(11) {
+                    let valid = faidx.is_valid_region(name, start, end);
+                    assert_eq!(valid, faidx.query(name, start, end).is_ok());
+                    assert_eq!(faidx.check_region(name, start, end).is_valid(), valid);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn from_bytes_and_mmap() -> Result<()> {
+        let index = FastaIndex::from_bytes(&std::fs::read(TEST_FASTA_INDEX)?)?;
+        assert_eq!(index, FastaIndex::from_filepath(TEST_FASTA_INDEX)?);
+        assert!(FastaIndex::from_bytes(b"chr1\t112\n").is_err());
+
+        let file = std::fs::File::open(TEST_FASTA)?;
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        let mut faidx = IndexedFasta::from_mmap(index, map);
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut expected = IndexedFasta::new(index, TEST_FASTA)?;
+        for name in ["chr1", "chr2"] {
+            let seq = faidx.query_all(name)?.to_vec();
+            assert_eq!(seq, expected.query_all(name)?);
+        }
+        assert_eq!(faidx.query_buffer("chr1", 0, 10)?, b"ACCTACGATC");
+        Ok(())
+    }
+
+    #[test]
+    fn in_memory_backend() -> Result<()> {
+        let data = std::fs::read(TEST_FASTA)?;
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::builder(index, "")
+            .backend(Backend::InMemory(data))
+            .build()?;
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut expected = IndexedFasta::new(index, TEST_FASTA)?;
+        for name in ["chr1", "chr2"] {
+            let seq = faidx.query_all(name)?.to_vec();
+            assert_eq!(seq, expected.query_all(name)?);
+        }
+        assert_eq!(faidx.query_buffer("chr2", 26, 30)?, b"GC\nCA");
+        assert_eq!(faidx.header("chr1")?, b">chr1");
+        assert!(faidx.query("chr1", 100, 200).is_err());
+
+        faidx.remap(TEST_FASTA)?;
+        assert_eq!(faidx.query("chr1", 0, 10)?, b"ACCTACGATC");
+        Ok(())
+    }
+
+    #[test]
+    fn masked_fraction() -> Result<()> {
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        // Intervals spanning line breaks
+        for (start, end) in [(0, 60), (10, 30), (35, 45), (59, 60)] {
+            let seq = faidx.query("chr1", start, end)?.to_vec();
+            let masked = seq.iter().filter(|c| c.is_ascii_lowercase()).count();
+            let expected = masked as f64 / seq.len() as f64;
+            assert_eq!(faidx.masked_fraction("chr1", start, end)?, expected);
+        }
+        assert_eq!(faidx.masked_fraction("chr2", 10, 20)?, 0.0);
+        assert!(faidx.masked_fraction("chr2", 10, 10).is_err());
+        assert!(faidx.masked_fraction("chr3", 0, 10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn indexed_fastq() -> Result<()> {
+        let index = FastqIndex::from_filepath(TEST_FASTQ_INDEX)?;
+        let mut fastq = IndexedFastq::new(index, TEST_FASTQ)?;
+        assert_eq!(fastq.query_seq("read1", 0, 10)?, b"ACGTACGTAC");
+        assert_eq!(fastq.query_qual("read1", 0, 10)?, b"IIIIIHHHHH");
+        assert_eq!(fastq.query_qual("read2", 14, 16)?, b"%%");
+        // A read wrapped over several lines
+        assert_eq!(fastq.seq_len("read3"), Some(12));
+        assert_eq!(fastq.query_seq("read3", 0, 12)?, b"ACGTACGTACGT");
+        assert_eq!(fastq.query_qual("read3", 0, 12)?, b"ABCDEFGHIJKL");
+
+        assert!(fastq.query_seq("read1", 0, 11).is_err());
+        assert!(fastq.query_qual("read1", 5, 5).is_err());
+        assert!(fastq.query_seq("read4", 0, 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn emit_contig() -> Result<()> {
+        let index = FastaIndex::from_filepath(EMPTY_CONTIG_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, EMPTY_CONTIG_FASTA)?;
+        faidx.set_aliases([("1".to_string(), "chr1".to_string())]);
+        let mut out = Vec::new();
+        faidx.emit_contig("1", 5, &mut out)?;
+        let expected = faidx.query_all("chr1")?;
+        let mut wrapped = b">chr1\n".to_vec();
+        for line in expected.chunks(5) {
+            wrapped.extend_from_slice(line);
+            wrapped.push(b'\n');
+        }
+        assert_eq!(out, wrapped);
+
+        // Exact multiples of the line width do not end with an empty line
+        out.clear();
+        faidx.emit_contig("chr1", 7, &mut out)?;
+        assert!(out.ends_with(b"\n") && !out.ends_with(b"\n\n"));
+        assert_eq!(out.iter().filter(|&&c| c == b'\n').count(), 3);
+
+        out.clear();
+        faidx.emit_contig("empty", 60, &mut out)?;
+        assert_eq!(out, b">empty\n");
+        assert!(faidx.emit_contig("chr1", 0, &mut out).is_err());
+        assert!(faidx.emit_contig("chr3", 60, &mut out).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_u64() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let expected = faidx.query("chr2", 20, 90)?.to_vec();
+        assert_eq!(faidx.query_u64("chr2", 20, 90)?, expected);
+        assert!(faidx.query_u64("chr2", 20, 200).is_err());
+        assert!(faidx.query_u64("chr3", 0, 10).is_err());
+        // The file offset of the end overflows u64
+        let err = faidx.query_u64("chr2", 0, u64::MAX).unwrap_err();
+        assert!(err.to_string().contains("overflows"));
+        Ok(())
+    }
+
+    #[test]
+    fn contig_cache() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::with_cache(index, TEST_FASTA, 300)?;
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut expected = IndexedFasta::new(index, TEST_FASTA)?;
+        assert_eq!(faidx.query("chr1", 0, 10)?, b"ACCTACGATC");
+        assert_eq!(faidx.query("chr2", 0, 10)?, b"TTTTGATCGA");
+        assert!(faidx.is_loaded("chr1") && faidx.is_loaded("chr2"));
+        for (name, start, end) in [("chr1", 20, 80), ("chr2", 50, 176), ("chr1", 111, 112)] {
+            let seq = faidx.query(name, start, end)?.to_vec();
+            assert_eq!(seq, expected.query(name, start, end)?);
+        }
+
+        // chr2 was used least recently
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::with_cache(index, TEST_FASTA, 200)?;
+        assert_eq!(faidx.query("chr1", 0, 10)?, b"ACCTACGATC");
+        assert_eq!(faidx.query("chr2", 0, 10)?, b"TTTTGATCGA");
+        assert!(!faidx.is_loaded("chr1"));
+        assert!(faidx.unload_contig("chr2"));
+        assert_eq!(faidx.query("chr1", 0, 10)?, b"ACCTACGATC");
+        assert!(faidx.is_loaded("chr1"));
+
+        // A contig larger than the cache is queried from the file
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::with_cache(index, TEST_FASTA, 150)?;
+        assert_eq!(faidx.query("chr2", 0, 4)?, b"TTTT");
+        assert!(!faidx.is_loaded("chr2"));
+        assert!(faidx.query("chr3", 0, 4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn query_with_newlines_restores_wrapping() -> Result<()> {
+        for (fasta, fai) in [
+            (TEST_FASTA, TEST_FASTA_INDEX),
+            (CRLF_FASTA, CRLF_FASTA_INDEX),
+        ] {
+            let index = FastaIndex::from_filepath(fai)?;
+            let mut faidx = IndexedFasta::new(index, fasta)?;
+            for (start, end) in [(0, 10), (0, 28), (20, 60), (27, 29), (84, 112)] {
+                let raw = faidx.query_buffer("chr1", start, end)?.to_vec();
+                let expected = faidx.query("chr1", start, end)?.to_vec();
+                let (seq, newlines) = faidx.query_with_newlines("chr1", start, end)?;
+                assert_eq!(seq, expected);
+                let mut rewrapped = Vec::new();
+                let mut last = 0;
+                for &pos in &newlines {
+                    rewrapped.extend_from_slice(&seq[last..pos]);
+                    rewrapped.extend_from_slice(if fasta == CRLF_FASTA { b"\r\n" } else { b"\n" });
+                    last = pos;
+                }
+                rewrapped.extend_from_slice(&seq[last..]);
+                assert_eq!(rewrapped, raw, "{} {}-{}", fasta, start, end);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn query_packed_roundtrip() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        for (start, end) in [(0, 1), (0, 4), (3, 60), (0, 176)] {
+            let seq = faidx.query("chr2", start, end)?.to_vec();
+            let (packed, len) = faidx.query_packed("chr2", start, end)?;
+            assert_eq!(len, seq.len());
+            assert_eq!(packed.len(), len.div_ceil(4));
+            let unpacked = (0..len)
+                .map(|i| b"ACGT"[((packed[i / 4] >> (6 - 2 * (i % 4))) & 0b11) as usize])
+                .collect::<Vec<_>>();
+            assert_eq!(unpacked, seq);
+        }
+
+        // Soft-masked bases are packed, ambiguous bases are not
+        let index = FastaIndex::from_filepath(MASKED_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, MASKED_FASTA)?;
+        assert_eq!(faidx.query_packed("chr1", 4, 12)?, (vec![0x1b, 0x1b], 8));
+        let err = faidx.query_packed("chr1", 10, 20).unwrap_err();
+        assert!(err.to_string().contains("position 16"));
+        Ok(())
+    }
+
+    #[test]
+    fn query_each_continues_past_errors() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        let regions = vec![
+            ("chr1", 20, 60),
+            ("chr1", 100, 200),
+            ("chr2", 10, 10),
+            ("chrX", 0, 10),
+            ("chr2", 150, 176),
+        ];
+        let results = faidx.query_each(regions.clone()).collect::<Vec<_>>();
+        assert_eq!(results.len(), regions.len());
+        for (result, &(name, start, end)) in results.iter().zip(&regions) {
+            match faidx.query(name, start, end) {
+                Ok(seq) => assert_eq!(result.as_ref().unwrap(), &seq.to_vec()),
+                Err(_) => assert!(result.is_err()),
+            }
+        }
+        let err = results[3].as_ref().unwrap_err();
+        assert!(err.to_string().contains("region 3 (chrX:0-10)"));
+        Ok(())
+    }
+
+    #[test]
+    fn composition_skip_gaps() -> Result<()> {
+        let index = FastaIndex::from_filepath(GAPPED_FASTA_INDEX)?;
+        let faidx = IndexedFasta::new(index, GAPPED_FASTA)?;
+        let skip = CompositionOpts::skip_gaps();
+
+        // ACGT--ACGT ..GGCC** over a line break
+        let counts = faidx.base_counts("seq1", 0, 18)?;
+        assert_eq!((counts.other, counts.total()), (6, 18));
+        let counts = faidx.base_counts_with("seq1", 0, 18, &skip)?;
+        assert_eq!(
+            (counts.c, counts.g, counts.other, counts.total()),
+            (4, 4, 0, 12)
+        );
+        assert_eq!(faidx.gc_content("seq1", 0, 18)?, 8.0 / 18.0);
+        assert_eq!(faidx.gc_content_with("seq1", 0, 18, &skip)?, 8.0 / 12.0);
+
+        // Only `-` is a gap
+        let dash = CompositionOpts {
+            skip_gaps: true,
+            gap_bytes: b"-".to_vec(),
+        };
+        assert_eq!(faidx.base_counts_with("seq1", 0, 18, &dash)?.other, 4);
+        assert_eq!(
+            faidx.gc_content_with("seq1", 0, 18, &CompositionOpts::default())?,
+            faidx.gc_content("seq1", 0, 18)?
+        );
+
+        assert_eq!(faidx.gc_content_with("seq2", 5, 12, &skip)?, 0.5);
+        assert!(faidx.gc_content_with("seq2", 0, 10, &skip).is_err());
+        assert_eq!(faidx.base_counts_with("seq2", 0, 10, &skip)?.total(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn query_stranded() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        faidx.load_contig("chr2")?;
+        for (name, start, end) in [("chr1", 20, 60), ("chr2", 100, 176)] {
+            let forward = faidx.query(name, start, end)?.to_vec();
+            let reverse = faidx.query_revcomp(name, start, end)?.to_vec();
+            assert_eq!(
+                faidx.query_stranded(name, start, end, Strand::Plus)?,
+                forward
+            );
+            assert_eq!(faidx.buffer(), forward);
+            assert_eq!(
+                faidx.query_stranded(name, start, end, Strand::Minus)?,
+                reverse
+            );
+        }
+        for (strand, c) in [(Strand::Plus, '+'), (Strand::Minus, '-')] {
+            assert_eq!(Strand::try_from(c)?, strand);
+        }
+        assert!(faidx.query_stranded("chr1", 0, 200, Strand::Minus).is_err());
+
+        let index = FastaIndex::from_filepath(PROTEIN_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, PROTEIN_FASTA)?;
+        let name = "sp|P0001|PROT1";
+        assert!(faidx.query_stranded(name, 0, 10, Strand::Plus).is_ok());
+        assert!(faidx.query_stranded(name, 0, 10, Strand::Minus).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bgzf_with_malformed_gzi() -> Result<()> {
+        // A FASTA file is not a `.gzi` index
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        assert!(IndexedFasta::new_bgzf(index, TEST_FASTA, TEST_FASTA_GZ).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_queries_across_aliases() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        faidx.set_aliases([("2".to_string(), "chr2".to_string())]);
+
+        // The cached entry is shared by a contig and its alias
+        for name in ["chr2", "2", "chr1", "2", "chr2"] {
+            let expected = if name == "chr1" {
+                &b"ACCTACGATC"[..]
+            } else {
+                &b"TTTTGATCGA"[..]
+            };
+            assert_eq!(faidx.query(name, 0, 10)?, expected);
+        }
+        assert!(faidx.query("2", 170, 180).is_err());
+        assert_eq!(faidx.query("2", 170, 176)?, b"ACCACA");
+
+        faidx.load_contig("chr2")?;
+        assert_eq!(faidx.query_uppercased("2", 0, 10)?, b"TTTTGATCGA");
+
+        // Replacing the aliases drops the entry cached for the alias
+        faidx.set_aliases([("2".to_string(), "chr1".to_string())]);
+        assert_eq!(faidx.query("2", 0, 10)?, b"ACCTACGATC");
+        Ok(())
+    }
 }
@@ -0,0 +1,64 @@
+use memchr::memchr2_iter;
+
+/// The line-ending style of a FASTA record.
+///
+/// This is inferred from the difference between the `line_width` and
+/// `line_bases` of an `IndexEntry`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Lines are terminated by a single `\n`.
+    Lf,
+    /// Lines are terminated by `\r\n`.
+    CrLf,
+    /// The sequence is not wrapped and has no line terminator.
+    None,
+}
+impl LineEnding {
+    /// Returns the bytes used to terminate a line in this style.
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+            LineEnding::None => b"",
+        }
+    }
+}
+
+/// Returns `true` if the byte is part of a line terminator (`\n` or `\r`).
+pub(crate) fn is_line_break(c: u8) -> bool {
+    c == b'\n' || c == b'\r'
+}
+
+/// Appends `seq` to `out` without its line terminators.
+///
+/// The terminators are located with `memchr` and the runs between them are copied
+/// in bulk rather than filtering byte by byte.
+pub(crate) fn extend_stripped(seq: &[u8], out: &mut Vec<u8>) {
+    out.reserve(seq.len());
+    let mut run_start = 0;
+    for pos in memchr2_iter(b'\n', b'\r', seq) {
+        out.extend_from_slice(&seq[run_start..pos]);
+        run_start = pos + 1;
+    }
+    out.extend_from_slice(&seq[run_start..]);
+}
+
+#[cfg(test)]
+mod testing {
+    use super::extend_stripped;
+
+    #[test]
+    fn strip_line_breaks() {
+        for (seq, expected) in [
+            (&b"ACGT\nACGT\nAC"[..], &b"ACGTACGTAC"[..]),
+            (b"ACGT\r\nACGT\r\n", b"ACGTACGT"),
+            (b"\nAC\n\nGT\n", b"ACGT"),
+            (b"ACGT", b"ACGT"),
+            (b"", b""),
+        ] {
+            let mut out = b"NN".to_vec();
+            extend_stripped(seq, &mut out);
+            assert_eq!(&out[2..], expected);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Conversions between `faiquery` and `noodles_fasta::fai` index types.
+//!
+//! This requires the `noodles` feature.
+
+use crate::{FastaIndex, IndexEntry};
+use anyhow::{anyhow, Error, Result};
+use noodles_fasta::fai;
+use std::num::NonZero;
+
+/// Converts an `IndexEntry` into a `noodles` index record.
+///
+/// Fails if the entry has zero line bases or a zero line width, which `noodles` does not
+/// represent.
+impl TryFrom<&IndexEntry> for fai::Record {
+    type Error = Error;
+
+    fn try_from(entry: &IndexEntry) -> Result<Self> {
+        let nonzero = |value: usize, field: &str| {
+            NonZero::new(value as u64)
+                .ok_or_else(|| anyhow!("Entry {} has a {} of zero", entry.name, field))
+        };
+        Ok(fai::Record::new(
+            entry.name.as_str(),
+            entry.length as u64,
+            entry.offset as u64,
+            nonzero(entry.line_bases, "line bases")?,
+            nonzero(entry.line_width, "line width")?,
+        ))
+    }
+}
+
+/// Converts a `noodles` index record into an `IndexEntry`.
+///
+/// Fails if the record name is not valid UTF-8 or its line geometry is rejected by
+/// `IndexEntry::new`.
+impl TryFrom<&fai::Record> for IndexEntry {
+    type Error = Error;
+
+    fn try_from(record: &fai::Record) -> Result<Self> {
+        let name = match std::str::from_utf8(record.name()) {
+            Ok(name) => name.to_string(),
+            Err(err) => return Err(anyhow!("Record name is not valid UTF-8: {}", err)),
+        };
+        IndexEntry::new(
+            &name,
+            record.length() as usize,
+            record.position() as usize,
+            record.line_base_count().get() as usize,
+            record.line_width().get() as usize,
+        )
+    }
+}
+
+/// Converts a `FastaIndex` into a `noodles` index with its records in insertion order.
+impl TryFrom<&FastaIndex> for fai::Index {
+    type Error = Error;
+
+    fn try_from(index: &FastaIndex) -> Result<Self> {
+        let records = (0..index.len())
+            .filter_map(|i| index.get_nth(i))
+            .map(fai::Record::try_from)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(fai::Index::from(records))
+    }
+}
+
+/// Converts a `noodles` index into a `FastaIndex`, preserving the record order.
+impl TryFrom<&fai::Index> for FastaIndex {
+    type Error = Error;
+
+    fn try_from(index: &fai::Index) -> Result<Self> {
+        let mut fasta_index = FastaIndex::new();
+        for record in index.as_ref() {
+            fasta_index.insert(IndexEntry::try_from(record)?)?;
+        }
+        Ok(fasta_index)
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use crate::{FastaIndex, IndexEntry, IndexedFasta};
+    use anyhow::Result;
+    use noodles_fasta::fai;
+    const TEST_FASTA: &str = "example_data/example.fa";
+    const TEST_FASTA_INDEX: &str = "example_data/example.fa.fai";
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let index = FastaIndex::from_filepath(TEST_FASTA_INDEX)?;
+        let noodles_index = fai::Index::try_from(&index)?;
+        let expected = fai::io::Reader::new(std::io::BufReader::new(std::fs::File::open(
+            TEST_FASTA_INDEX,
+        )?))
+        .read_index()?;
+        assert_eq!(noodles_index, expected);
+
+        let index = FastaIndex::try_from(&noodles_index)?;
+        assert_eq!(index.get_nth(1).unwrap().name, "chr2");
+        let mut faidx = IndexedFasta::new(index, TEST_FASTA)?;
+        assert_eq!(faidx.query("chr2", 0, 10)?, b"TTTTGATCGA");
+        Ok(())
+    }
+
+    #[test]
+    fn zero_line_bases() {
+        let entry = IndexEntry {
+            name: "empty".to_string(),
+            length: 0,
+            offset: 7,
+            line_bases: 0,
+            line_width: 0,
+        };
+        assert!(fai::Record::try_from(&entry).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::IndexEntry;
+use anyhow::{bail, Result};
+
+/// The line geometry of an `IndexEntry` without its name.
+///
+/// This is `Copy` so it can be cached without cloning the entry.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LineGeometry {
+    pub length: usize,
+    pub offset: usize,
+    pub line_bases: usize,
+    pub line_width: usize,
+}
+impl From<&IndexEntry> for LineGeometry {
+    fn from(entry: &IndexEntry) -> Self {
+        Self {
+            length: entry.length,
+            offset: entry.offset,
+            line_bases: entry.line_bases,
+            line_width: entry.line_width,
+        }
+    }
+}
+
+/// A query position.
+///
+/// This struct is used to calculate the position of a query in a FASTA file.
+/// It is used to calculate the offset and size of the query in the memory-mapped file
+/// and keeps the line geometry needed to copy the bases out of it.
+pub(crate) struct QueryPosition {
+    pub buffer_size: usize,
+    pub pos: usize,
+    /// The number of line terminator bytes at the end of `buffer_size` that follow the
+    /// last base of the contig, which may be missing at the end of the file.
+    pub trailing: usize,
+    size: usize,
+    col_pos: usize,
+    line_bases: usize,
+    line_width: usize,
+}
+impl QueryPosition {
+    pub fn new(start: usize, end: usize, entry: &IndexEntry) -> Self {
+        Self::from_geometry(start, end, LineGeometry::from(entry))
+    }
+
+    /// Creates a query position from the line geometry of an entry.
+    pub fn from_geometry(start: usize, end: usize, entry: LineGeometry) -> Self {
+        let size = end - start;
+        let row_pos = (start / entry.line_bases) * entry.line_width;
+        let col_pos = start % entry.line_bases;
+        let num_lines = (size + col_pos) / entry.line_bases;
+        // Each line is terminated by `line_width - line_bases` bytes (`\n` or `\r\n`)
+        let buffer_size = size + num_lines * (entry.line_width - entry.line_bases);
+        let pos = entry.offset + row_pos + col_pos;
+        let trailing = if end == entry.length && (size + col_pos).is_multiple_of(entry.line_bases) {
+            entry.line_width - entry.line_bases
+        } else {
+            0
+        };
+        Self {
+            buffer_size,
+            pos,
+            trailing,
+            size,
+            col_pos,
+            line_bases: entry.line_bases,
+            line_width: entry.line_width,
+        }
+    }
+
+    /// Returns the number of raw bytes of the query within a file of `file_len` bytes.
+    ///
+    /// This is `buffer_size` unless the final line of the contig is not terminated at
+    /// the end of the file, in which case the missing terminator bytes are excluded.
+    pub fn clamped_len(&self, file_len: usize) -> usize {
+        let excess = (self.pos + self.buffer_size).saturating_sub(file_len);
+        self.buffer_size - excess.min(self.trailing)
+    }
+
+    /// Appends the bases of the query to `out` from its `raw` bytes (including newlines).
+    ///
+    /// Rather than scanning for line terminators, each line's bases are copied directly
+    /// using the line geometry of the entry: the first line from the query column and
+    /// every following line from its start, skipping `line_width - line_bases` bytes.
+    pub fn extend_bases(&self, raw: &[u8], out: &mut Vec<u8>) {
+        out.reserve(self.size);
+        let mut remaining = self.size;
+        let mut line_start = 0;
+        let mut line_len = self.line_bases - self.col_pos;
+        while remaining > 0 {
+            let len = line_len.min(remaining);
+            out.extend_from_slice(&raw[line_start..line_start + len]);
+            remaining -= len;
+            line_start += line_len + self.line_width - self.line_bases;
+            line_len = self.line_bases;
+        }
+    }
+}
+
+/// Validate the start and end positions of a query interval.
+///
+/// If the interval is not `bounded` the `end` position may exceed the sequence length.
+pub(crate) fn validate_interval(
+    entry: &IndexEntry,
+    start: usize,
+    end: usize,
+    bounded: bool,
+) -> Result<()> {
+    validate_bounds(entry.length, start, end, bounded)
+}
+
+/// Validate the start and end positions of a query interval on a sequence of `length` bases.
+///
+/// If the interval is not `bounded` the `end` position may exceed the sequence length.
+pub(crate) fn validate_bounds(
+    length: usize,
+    start: usize,
+    end: usize,
+    bounded: bool,
+) -> Result<()> {
+    if start > end {
+        bail!("Start position must be less than end position");
+    } else if start == end {
+        bail!("Start and end positions must not be equal");
+    } else if start >= length {
+        bail!("Start position must be less than sequence length");
+    } else if bounded && end > length {
+        bail!("End position must be less than sequence length");
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::IndexedFasta;
+use anyhow::Result;
+
+/// A cursor over the full sequence of every record in file order.
+///
+/// The cursor owns the record names and borrows the `IndexedFasta` only for the
+/// duration of each `next_record` call, so the returned sequence can be read from the
+/// fasta's internal buffer.
+///
+/// Created with `IndexedFasta::records`.
+#[derive(Debug, Clone)]
+pub struct RecordCursor {
+    names: Vec<String>,
+    idx: usize,
+}
+impl RecordCursor {
+    pub(crate) fn new(names: Vec<String>) -> Self {
+        Self { names, idx: 0 }
+    }
+
+    /// Returns the name and full sequence of the next record or `None` if exhausted.
+    ///
+    /// Line terminators are removed from the sequence as in `IndexedFasta::query_all`.
+    pub fn next_record<'a>(
+        &mut self,
+        fasta: &'a mut IndexedFasta,
+    ) -> Option<Result<(&str, &'a [u8])>> {
+        let name = self.names.get(self.idx)?;
+        self.idx += 1;
+        Some(fasta.query_all(name).map(|seq| (name.as_str(), seq)))
+    }
+
+    /// Returns the number of records not yet visited.
+    pub fn remaining(&self) -> usize {
+        self.names.len() - self.idx
+    }
+}
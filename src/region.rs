@@ -0,0 +1,66 @@
+use anyhow::{bail, Result};
+
+/// Parses a `name:start-end` region string into its name and 1-based inclusive coordinates.
+///
+/// The name is split at the last `:` so that names containing colons are supported,
+/// and thousands separators (e.g. `chr1:1,000-2,000`) are ignored in the coordinates.
+pub(crate) fn parse_region(region: &str) -> Result<(&str, usize, usize)> {
+    let (name, interval) = match region.rsplit_once(':') {
+        Some((name, interval)) if !name.is_empty() => (name, interval),
+        _ => bail!("Region {} is not of the form name:start-end", region),
+    };
+    let (start, end) = match interval.split_once('-') {
+        Some(bounds) => bounds,
+        None => bail!("Region {} is not of the form name:start-end", region),
+    };
+    let parse = |position: &str| match position.replace(',', "").parse::<usize>() {
+        Ok(position) => Ok(position),
+        Err(_) => bail!("Invalid position {} in region {}", position, region),
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if start == 0 {
+        bail!(
+            "1-based start position must be at least 1 (found 0 in {})",
+            region
+        );
+    }
+    if start > end {
+        bail!("Start position is greater than end position in {}", region);
+    }
+    Ok((name, start, end))
+}
+
+#[cfg(test)]
+mod testing {
+    use super::parse_region;
+
+    #[test]
+    fn parse_valid_regions() {
+        assert_eq!(parse_region("chr1:1-10").unwrap(), ("chr1", 1, 10));
+        assert_eq!(parse_region("chr1:5-5").unwrap(), ("chr1", 5, 5));
+        assert_eq!(
+            parse_region("chr1:1,000-2,000").unwrap(),
+            ("chr1", 1000, 2000)
+        );
+        assert_eq!(
+            parse_region("HLA-A*01:01:01:01:10-20").unwrap(),
+            ("HLA-A*01:01:01:01", 10, 20)
+        );
+    }
+
+    #[test]
+    fn parse_invalid_regions() {
+        for region in [
+            "chr1",
+            "chr1:10",
+            ":1-10",
+            "chr1:a-10",
+            "chr1:1-",
+            "chr1:-10",
+            "chr1:0-10",
+            "chr1:10-5",
+        ] {
+            assert!(parse_region(region).is_err(), "{}", region);
+        }
+    }
+}
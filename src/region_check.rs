@@ -0,0 +1,31 @@
+/// The result of checking whether a region can be queried.
+///
+/// Every variant for a known contig carries the contig `length` so that callers can
+/// report the valid range (e.g. "requested 100-500 but chr1 is only 400 bp").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionCheck {
+    /// The region lies entirely within the contig.
+    Valid { length: usize },
+    /// The contig is not present in the index.
+    MissingContig,
+    /// The `start` position is not less than the `end` position.
+    EmptyInterval { length: usize },
+    /// The region extends past the end of the contig.
+    OutOfBounds { length: usize },
+}
+impl RegionCheck {
+    /// Returns `true` if the region can be queried.
+    pub fn is_valid(&self) -> bool {
+        matches!(self, RegionCheck::Valid { .. })
+    }
+
+    /// Returns the length of the contig or `None` if it is missing.
+    pub fn contig_length(&self) -> Option<usize> {
+        match self {
+            RegionCheck::Valid { length }
+            | RegionCheck::EmptyInterval { length }
+            | RegionCheck::OutOfBounds { length } => Some(*length),
+            RegionCheck::MissingContig => None,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::complement::DNA_COMPLEMENT;
+use anyhow::{bail, Result};
+use std::ops::Deref;
+
+/// A sequence returned by `IndexedFasta::query`.
+///
+/// This wraps the queried bytes and dereferences to `[u8]`, so it can be used wherever
+/// a byte slice is expected (e.g. `seq.len()`, `seq.to_vec()`, or `&seq[..4]`) while
+/// adding a few helpers for common operations on a sequence.
+/// It borrows the `IndexedFasta` it was queried from until it is dropped, and it is
+/// `#[must_use]` so a query whose result is accidentally discarded raises a warning.
+///
+/// # Example
+///
+/// ```
+/// use faiquery::{FastaIndex, IndexedFasta};
+///
+/// let index = FastaIndex::from_filepath("example_data/example.fa.fai")
+///     .expect("Could not read index file");
+/// let mut faidx = IndexedFasta::new(index, "example_data/example.fa")
+///     .expect("Could not read FASTA file");
+///
+/// // ACCTACGATC
+/// let seq = faidx.query("chr1", 0, 10).unwrap();
+/// assert_eq!(seq.len(), 10);
+/// assert_eq!(seq.gc(), 0.5);
+/// assert_eq!(seq.as_str().unwrap(), "ACCTACGATC");
+/// assert_eq!(seq.revcomp_owned(), b"GATCGTAGGT");
+/// assert_eq!(seq, b"ACCTACGATC");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct Sequence<'a>(&'a [u8]);
+impl<'a> Sequence<'a> {
+    pub(crate) fn new(seq: &'a [u8]) -> Self {
+        Self(seq)
+    }
+
+    /// Returns the underlying bytes with the lifetime of the query.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Returns the number of bases in the sequence.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the sequence has no bases.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the fraction of bases that are `G` or `C` (case-insensitive).
+    ///
+    /// An empty sequence has a GC content of `0.0`.
+    pub fn gc(&self) -> f64 {
+        if self.0.is_empty() {
+            return 0.0;
+        }
+        let gc = self
+            .0
+            .iter()
+            .filter(|&&c| matches!(c, b'G' | b'C' | b'g' | b'c'))
+            .count();
+        gc as f64 / self.0.len() as f64
+    }
+
+    /// Returns the sequence as a `&str`.
+    ///
+    /// # Errors
+    ///
+    /// Error if the sequence is not valid UTF-8.
+    pub fn as_str(&self) -> Result<&'a str> {
+        match std::str::from_utf8(self.0) {
+            Ok(seq) => Ok(seq),
+            Err(err) => bail!("Sequence is not valid UTF-8: {}", err),
+        }
+    }
+
+    /// Returns an owned copy of the reverse complement of the sequence.
+    ///
+    /// Bases are complemented with `DNA_COMPLEMENT`, so case is preserved and bytes
+    /// other than `ACGTN` are left unchanged.
+    pub fn revcomp_owned(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .rev()
+            .map(|&c| DNA_COMPLEMENT[c as usize])
+            .collect()
+    }
+}
+
+impl Deref for Sequence<'_> {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Sequence<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+impl<'a> From<Sequence<'a>> for &'a [u8] {
+    fn from(seq: Sequence<'a>) -> Self {
+        seq.0
+    }
+}
+
+impl PartialEq<[u8]> for Sequence<'_> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&[u8]> for Sequence<'_> {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for Sequence<'_> {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.0 == other
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for Sequence<'_> {
+    fn eq(&self, other: &&[u8; N]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Vec<u8>> for Sequence<'_> {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.0 == other.as_slice()
+    }
+}
+
+impl PartialEq<Sequence<'_>> for Vec<u8> {
+    fn eq(&self, other: &Sequence<'_>) -> bool {
+        self.as_slice() == other.0
+    }
+}
+
+impl PartialEq<Sequence<'_>> for &[u8] {
+    fn eq(&self, other: &Sequence<'_>) -> bool {
+        *self == other.0
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::Sequence;
+
+    #[test]
+    fn helpers() {
+        let seq = Sequence::new(b"ACGTnnGc");
+        assert_eq!(seq.len(), 8);
+        assert!(!seq.is_empty());
+        assert_eq!(seq.gc(), 0.5);
+        assert_eq!(seq.as_str().unwrap(), "ACGTnnGc");
+        assert_eq!(seq.revcomp_owned(), b"gCnnACGT");
+        assert_eq!(&seq[..4], b"ACGT");
+
+        let empty = Sequence::new(b"");
+        assert!(empty.is_empty());
+        assert_eq!(empty.gc(), 0.0);
+        assert!(Sequence::new(&[0xff, 0xfe]).as_str().is_err());
+    }
+
+    #[test]
+    fn comparisons() {
+        let bytes = b"ACGT".to_vec();
+        let seq = Sequence::new(&bytes);
+        assert_eq!(seq, b"ACGT");
+        assert_eq!(seq, *b"ACGT");
+        assert_eq!(seq, &bytes[..]);
+        assert_eq!(seq, bytes);
+        assert_eq!(bytes, seq);
+        assert_eq!(&bytes[..], seq);
+        let slice: &[u8] = seq.into();
+        assert_eq!(slice, b"ACGT");
+    }
+}
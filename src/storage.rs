@@ -0,0 +1,198 @@
+use crate::{bgzf::GziIndex, query_position::QueryPosition, Advice};
+use anyhow::{bail, Result};
+use memmap2::Mmap;
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    sync::Mutex,
+};
+
+/// The backing storage of an `IndexedFasta`.
+///
+/// All offsets are positions in the uncompressed FASTA file so that every
+/// backend shares the same `QueryPosition` logic.
+#[derive(Debug)]
+pub(crate) enum Storage {
+    /// A memory-mapped plain-text FASTA file.
+    Mmap(Mmap),
+    /// A memory-mapped BGZF-compressed FASTA file and its `.gzi` index.
+    Bgzf { map: Mmap, gzi: GziIndex },
+    /// An open plain-text FASTA file read with `seek` and `read_exact`.
+    ///
+    /// The file is behind a `Mutex` so that the seek and read of a query are not
+    /// interleaved with another thread's.
+    File(Mutex<File>),
+    /// A plain-text FASTA file held entirely in memory.
+    InMemory(Vec<u8>),
+}
+impl Storage {
+    /// Returns the length of the uncompressed file.
+    pub fn len(&self) -> Result<usize> {
+        match self {
+            Storage::Mmap(map) => Ok(map.len()),
+            Storage::Bgzf { map, gzi } => gzi.uncompressed_len(map),
+            Storage::File(file) => Ok(lock(file)?.metadata()?.len() as usize),
+            Storage::InMemory(data) => Ok(data.len()),
+        }
+    }
+
+    /// Returns the raw bytes at `pos..pos + len`.
+    ///
+    /// This borrows directly from the memory map (or the in-memory file) when the file is
+    /// uncompressed, inflates the spanning blocks when it is BGZF-compressed, and reads
+    /// the bytes from the file when it is not memory-mapped.
+    ///
+    /// # Errors
+    ///
+    /// Error if the bytes extend beyond the end of the file, e.g. when the index
+    /// offsets are wrong or the file is truncated.
+    pub fn fetch(&self, pos: usize, len: usize) -> Result<Cow<'_, [u8]>> {
+        match self {
+            Storage::Mmap(map) => {
+                check_bounds(pos, len, map.len())?;
+                Ok(Cow::Borrowed(&map[pos..pos + len]))
+            }
+            Storage::Bgzf { map, gzi } => match gzi.read(map, pos, len) {
+                Ok(buffer) => Ok(Cow::Owned(buffer)),
+                Err(err) => bail!(
+                    "Bytes {}..{} could not be read from the BGZF file: {}",
+                    pos,
+                    pos + len,
+                    err
+                ),
+            },
+            Storage::File(file) => {
+                let mut file = lock(file)?;
+                check_bounds(pos, len, file.metadata()?.len() as usize)?;
+                let mut buffer = vec![0; len];
+                file.seek(SeekFrom::Start(pos as u64))?;
+                file.read_exact(&mut buffer)?;
+                Ok(Cow::Owned(buffer))
+            }
+            Storage::InMemory(data) => {
+                check_bounds(pos, len, data.len())?;
+                Ok(Cow::Borrowed(&data[pos..pos + len]))
+            }
+        }
+    }
+
+    /// Returns the raw bytes of a query.
+    ///
+    /// This is `fetch` of the query's bytes, except that a missing line terminator after
+    /// the last base of a contig at the end of the file is tolerated.
+    pub fn fetch_query(&self, query_pos: &QueryPosition) -> Result<Cow<'_, [u8]>> {
+        let len = match self {
+            Storage::Mmap(map) => query_pos.clamped_len(map.len()),
+            Storage::File(file) => query_pos.clamped_len(lock(file)?.metadata()?.len() as usize),
+            Storage::InMemory(data) => query_pos.clamped_len(data.len()),
+            // The uncompressed length is expensive to compute so only retry on failure
+            Storage::Bgzf { .. } => match self.fetch(query_pos.pos, query_pos.buffer_size) {
+                Err(_) if query_pos.trailing > 0 => query_pos.buffer_size - query_pos.trailing,
+                result => return result,
+            },
+        };
+        self.fetch(query_pos.pos, len)
+    }
+
+    /// Returns the raw bytes of a query without copying (see `fetch_query` and `slice`).
+    pub fn slice_query(&self, query_pos: &QueryPosition) -> Result<&[u8]> {
+        let len = match self {
+            Storage::Mmap(map) => query_pos.clamped_len(map.len()),
+            Storage::InMemory(data) => query_pos.clamped_len(data.len()),
+            _ => query_pos.buffer_size,
+        };
+        self.slice(query_pos.pos, len)
+    }
+
+    /// Appends the bases of a query to `out` without their line terminators.
+    pub fn extend_bases(&self, query_pos: &QueryPosition, out: &mut Vec<u8>) -> Result<()> {
+        let seq_slice = self.fetch_query(query_pos)?;
+        query_pos.extend_bases(&seq_slice, out);
+        Ok(())
+    }
+
+    /// Advises the kernel of the expected access pattern of the memory map.
+    ///
+    /// This is a no-op when the file is not memory-mapped or the platform lacks `madvise`.
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    pub fn advise(&self, advice: Advice) -> Result<()> {
+        match self {
+            #[cfg(unix)]
+            Storage::Mmap(map) | Storage::Bgzf { map, .. } => Ok(map.advise(advice.into())?),
+            _ => Ok(()),
+        }
+    }
+
+    /// Advises the kernel that the bytes of a query will be needed soon.
+    ///
+    /// This is a no-op when the file is not an uncompressed memory map or the platform
+    /// lacks `madvise`.
+    ///
+    /// # Errors
+    ///
+    /// Error if the bytes extend beyond the end of the file (see `fetch`).
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    pub fn prefetch(&self, query_pos: &QueryPosition) -> Result<()> {
+        match self {
+            Storage::Mmap(map) => {
+                let (pos, len) = (query_pos.pos, query_pos.clamped_len(map.len()));
+                check_bounds(pos, len, map.len())?;
+                #[cfg(unix)]
+                map.advise_range(memmap2::Advice::WillNeed, pos, len)?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the raw bytes at `pos..pos + len` without copying.
+    ///
+    /// This is only possible when the file is uncompressed and memory-mapped or held in
+    /// memory.
+    ///
+    /// # Errors
+    ///
+    /// Error if the bytes extend beyond the end of the file (see `fetch`).
+    pub fn slice(&self, pos: usize, len: usize) -> Result<&[u8]> {
+        match self {
+            Storage::Mmap(map) => {
+                check_bounds(pos, len, map.len())?;
+                Ok(&map[pos..pos + len])
+            }
+            Storage::InMemory(data) => {
+                check_bounds(pos, len, data.len())?;
+                Ok(&data[pos..pos + len])
+            }
+            Storage::Bgzf { .. } => {
+                bail!("Zero-copy queries are not supported on BGZF-compressed FASTA files")
+            }
+            Storage::File(_) => {
+                bail!(
+                    "Zero-copy queries are not supported on FASTA files that are not memory-mapped"
+                )
+            }
+        }
+    }
+}
+
+/// Checks that the bytes at `pos..pos + len` lie within a file of `file_len` bytes.
+fn check_bounds(pos: usize, len: usize, file_len: usize) -> Result<()> {
+    if pos.saturating_add(len) > file_len {
+        bail!(
+            "Bytes {}..{} are beyond the end of the file ({} bytes); the index may not match the FASTA or the FASTA may be truncated",
+            pos,
+            pos.saturating_add(len),
+            file_len
+        );
+    }
+    Ok(())
+}
+
+/// Locks the file of a `Storage::File`.
+fn lock(file: &Mutex<File>) -> Result<std::sync::MutexGuard<'_, File>> {
+    match file.lock() {
+        Ok(guard) => Ok(guard),
+        Err(_) => bail!("FASTA file lock was poisoned"),
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::{bail, Error, Result};
+
+/// The strand of a feature, as given by the `+`/`-` column of BED and GFF files.
+///
+/// See `IndexedFasta::query_stranded`.
+///
+/// # Example
+///
+/// ```
+/// use faiquery::Strand;
+///
+/// assert_eq!(Strand::try_from('-').unwrap(), Strand::Minus);
+/// assert!(Strand::try_from('.').is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strand {
+    /// The forward strand (`+`).
+    #[default]
+    Plus,
+    /// The reverse strand (`-`), read as the reverse complement.
+    Minus,
+}
+
+/// Parses a strand from `+` or `-`.
+impl TryFrom<char> for Strand {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self> {
+        match c {
+            '+' => Ok(Strand::Plus),
+            '-' => Ok(Strand::Minus),
+            _ => bail!("Invalid strand {} (expected + or -)", c),
+        }
+    }
+}
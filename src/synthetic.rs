@@ -0,0 +1,126 @@
+use crate::{FastaIndex, IndexEntry, IndexedFasta};
+use anyhow::{bail, Result};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+};
+
+/// Writes a synthetic FASTA file and its `.fai` index, then opens it.
+///
+/// Every contig is a reproducible pseudo-random sequence of `ACGT` wrapped at
+/// `line_bases` bases per line, so the same arguments always produce the same files.
+/// The FASTA is written to `path` and the index to `path` with a `.fai` suffix.
+///
+/// This is meant for generating large inputs for tests and benchmarks without
+/// shipping them with the crate.
+///
+/// # Errors
+///
+/// - Error if `line_bases` is zero.
+/// - Error if either file cannot be written.
+///
+/// # Example
+///
+/// ```
+/// use faiquery::write_synthetic_fasta;
+///
+/// let path = std::env::temp_dir().join("faiquery_doc_synthetic.fa");
+/// let contigs = [("chr1", 1000), ("chr2", 50)];
+/// let mut faidx = write_synthetic_fasta(path.to_str().unwrap(), &contigs, 60)
+///     .expect("Could not write FASTA file");
+///
+/// assert_eq!(faidx.seq_len("chr1"), Some(1000));
+/// assert_eq!(faidx.query("chr2", 0, 50).unwrap().len(), 50);
+/// ```
+pub fn write_synthetic_fasta(
+    path: &str,
+    contigs: &[(&str, usize)],
+    line_bases: usize,
+) -> Result<IndexedFasta> {
+    if line_bases == 0 {
+        bail!("Line bases must be greater than zero");
+    }
+    let mut fasta = BufWriter::new(File::create(path)?);
+    let mut index = FastaIndex::new();
+    let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+    let mut offset = 0;
+    let mut line = Vec::with_capacity(line_bases + 1);
+    for &(name, length) in contigs {
+        let header = format!(">{}\n", name);
+        fasta.write_all(header.as_bytes())?;
+        offset += header.len();
+        index.insert(IndexEntry {
+            name: name.to_string(),
+            length,
+            offset,
+            line_bases,
+            line_width: line_bases + 1,
+        })?;
+
+        let mut remaining = length;
+        while remaining > 0 {
+            line.clear();
+            line.extend((0..remaining.min(line_bases)).map(|_| rng.base()));
+            line.push(b'\n');
+            fasta.write_all(&line)?;
+            remaining -= line.len() - 1;
+            offset += line.len();
+        }
+    }
+    fasta.flush()?;
+
+    let mut fai = String::new();
+    for entry in (0..index.len()).filter_map(|i| index.get_nth(i)) {
+        fai.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            entry.name, entry.length, entry.offset, entry.line_bases, entry.line_width
+        ));
+    }
+    fs::write(format!("{}.fai", path), fai)?;
+    IndexedFasta::new(index, path)
+}
+
+/// A xorshift64 generator, which is plenty for arbitrary-looking sequence.
+struct XorShift(u64);
+impl XorShift {
+    fn base(&mut self) -> u8 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        b"ACGT"[(self.0 >> 62) as usize]
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::write_synthetic_fasta;
+    use crate::FastaIndex;
+    use anyhow::Result;
+
+    #[test]
+    fn synthetic_fasta_round_trip() -> Result<()> {
+        let path = std::env::temp_dir().join("faiquery_synthetic.fa");
+        let path = path.to_str().unwrap();
+        let contigs = [("chr1", 1000), ("chr2", 120), ("chr3", 7)];
+        let mut faidx = write_synthetic_fasta(path, &contigs, 60)?;
+        faidx.validate()?;
+
+        let index = FastaIndex::from_filepath(&format!("{}.fai", path))?;
+        assert!(index.verify_lengths(path)?.is_empty());
+        let names = (0..index.len())
+            .map(|i| index.get_nth(i).unwrap().name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["chr1", "chr2", "chr3"]);
+
+        let seq = faidx.query_all("chr1")?.to_vec();
+        assert_eq!(seq.len(), 1000);
+        assert!(seq.iter().all(|c| b"ACGT".contains(c)));
+
+        // The same arguments produce the same sequence
+        let mut again = write_synthetic_fasta(path, &contigs, 60)?;
+        assert_eq!(again.query_all("chr1")?, seq);
+
+        assert!(write_synthetic_fasta(path, &contigs, 0).is_err());
+        Ok(())
+    }
+}
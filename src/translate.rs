@@ -0,0 +1,58 @@
+/// The standard genetic code indexed by codon, with bases ordered `T`, `C`, `A`, `G`.
+const STANDARD_CODE: &[u8; 64] =
+    b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+
+/// Returns the index of a nucleotide in the `T`, `C`, `A`, `G` ordering of `STANDARD_CODE`.
+fn base_index(c: u8) -> Option<usize> {
+    match c.to_ascii_uppercase() {
+        b'T' | b'U' => Some(0),
+        b'C' => Some(1),
+        b'A' => Some(2),
+        b'G' => Some(3),
+        _ => None,
+    }
+}
+
+/// Translates a single codon with the standard genetic code.
+///
+/// Stop codons are translated to `*` and codons containing any base other than
+/// `A`, `C`, `G`, `T`, or `U` (e.g. `N`) are translated to `X`.
+pub(crate) fn translate_codon(codon: &[u8]) -> u8 {
+    match (
+        base_index(codon[0]),
+        base_index(codon[1]),
+        base_index(codon[2]),
+    ) {
+        (Some(a), Some(b), Some(c)) => STANDARD_CODE[16 * a + 4 * b + c],
+        _ => b'X',
+    }
+}
+
+/// Translates a sequence codon by codon, dropping a partial trailing codon.
+pub(crate) fn translate(seq: &[u8]) -> Vec<u8> {
+    seq.chunks_exact(3).map(translate_codon).collect()
+}
+
+#[cfg(test)]
+mod testing {
+    use super::{translate, translate_codon};
+
+    #[test]
+    fn standard_code() {
+        assert_eq!(translate_codon(b"ATG"), b'M');
+        assert_eq!(translate_codon(b"TGG"), b'W');
+        assert_eq!(translate_codon(b"GCC"), b'A');
+        assert_eq!(translate_codon(b"aug"), b'M');
+        for stop in [b"TAA", b"TAG", b"TGA"] {
+            assert_eq!(translate_codon(stop), b'*');
+        }
+        assert_eq!(translate_codon(b"ANG"), b'X');
+        assert_eq!(translate_codon(b"ARG"), b'X');
+    }
+
+    #[test]
+    fn drop_partial_codon() {
+        assert_eq!(translate(b"ATGGCCTAAGC"), b"MA*");
+        assert_eq!(translate(b"AT"), b"");
+    }
+}
@@ -0,0 +1,64 @@
+use crate::IndexedFasta;
+use anyhow::Result;
+
+/// A cursor over fixed-size windows of a contig.
+///
+/// The cursor owns the window coordinates and borrows the `IndexedFasta` only for the
+/// duration of each `next_window` call, so the returned sequence can be read from the
+/// fasta's internal buffer.
+///
+/// Windows of `size` bases start every `step` bases from the start of the contig.
+/// The last window is truncated at the contig end rather than dropped, and no windows
+/// are yielded after the one reaching the contig end.
+///
+/// Created with `IndexedFasta::windows`.
+#[derive(Debug, Clone)]
+pub struct WindowCursor {
+    name: String,
+    length: usize,
+    size: usize,
+    step: usize,
+    start: usize,
+    done: bool,
+}
+impl WindowCursor {
+    pub(crate) fn new(name: &str, length: usize, size: usize, step: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            length,
+            size,
+            step,
+            start: 0,
+            done: length == 0,
+        }
+    }
+
+    /// Returns the `(start, end)` interval of the next window or `None` if exhausted.
+    ///
+    /// This advances the cursor without reading any sequence.
+    pub fn next_interval(&mut self) -> Option<(usize, usize)> {
+        if self.done {
+            return None;
+        }
+        let start = self.start;
+        let end = (start + self.size).min(self.length);
+        self.start += self.step;
+        self.done = end == self.length || self.start >= self.length;
+        Some((start, end))
+    }
+
+    /// Returns the start position and sequence of the next window or `None` if exhausted.
+    ///
+    /// Newline characters are removed from the sequence as in `IndexedFasta::query`.
+    pub fn next_window<'a>(
+        &mut self,
+        fasta: &'a mut IndexedFasta,
+    ) -> Option<Result<(usize, &'a [u8])>> {
+        let (start, end) = self.next_interval()?;
+        Some(
+            fasta
+                .fill_buffer(&self.name, start, end, true)
+                .map(|seq| (start, seq)),
+        )
+    }
+}